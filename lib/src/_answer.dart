@@ -16,13 +16,19 @@ class _Answer {
   /// The [json] parameter should be a list of dynamic objects representing
   /// DNS records. Returns an [_Answer] instance containing parsed records
   /// and Service (SRV) records.
-  factory _Answer.fromJson(List<dynamic>? json) {
+  factory _Answer.fromJson(
+    List<dynamic>? json, [
+    DNSSection section = DNSSection.answer,
+  ]) {
     if (json == null) {
       return const _Answer(null);
     }
 
     final records = json
-        .map((answer) => _Record.fromJson(answer as Map<String, dynamic>))
+        .map(
+          (answer) =>
+              _Record.fromJson(answer as Map<String, dynamic>, section),
+        )
         .toList();
     final srvs = <SRVRecord>[];
 
@@ -59,28 +65,268 @@ class _Answer {
     return _Answer(records, srvs);
   }
 
+  /// The records of this answer grouped by the presentation name of their
+  /// type, e.g. `A` or `MX`, in order of first appearance.
+  Map<String, List<_Record>> get groupedByType {
+    final groups = <String, List<_Record>>{};
+    for (final record in records ?? const <_Record>[]) {
+      groups.putIfAbsent(record.typeName, () => []).add(record);
+    }
+
+    return groups;
+  }
+
+  /// The Uniform Resource Identifier (URI) records of this answer.
+  ///
+  /// The target may be quoted or not, depending on the provider. Throws a
+  /// [URIRecordFormatException] if a URI record is malformed, like SRV records
+  /// are rejected with an [SRVRecordFormatException].
+  List<URIRecord> get uris {
+    final regExp = RegExp(r'^(\d+)\s+(\d+)\s+(?:"(.+)"|([^\s"]+))$');
+
+    return [
+      for (final record in records ?? const <_Record>[])
+        if (record.rType == RecordType.uri)
+          switch (regExp.firstMatch(record.data.trim())) {
+            final match? => URIRecord(
+                priority: int.parse(match.group(1)!),
+                weight: int.parse(match.group(2)!),
+                target: match.group(3) ?? match.group(4)!,
+                fqdn: record.name,
+              ),
+            null => throw URIRecordFormatException(
+                'Failed to parse the URI record "${record.data}"',
+              ),
+          },
+    ];
+  }
+
+  /// Returns a copy of this answer with MX records ordered by preference and
+  /// SRV records ordered by ascending priority, then descending weight, as
+  /// RFC 2782 favors heavier records.
+  ///
+  /// Records are only reordered among the slots of their own type, and ties
+  /// keep their original order. [srvs] are ordered the same way. No weighted
+  /// shuffle is applied to SRV records of equal priority, see
+  /// [SRVRecord.shuffle] for that.
+  _Answer sorted() {
+    // The priority (or MX preference) and weight of a record.
+    (int, int) key(_Record record) {
+      final fields = record.data.split(RegExp(r'\s+')).map(int.tryParse);
+      final weight =
+          record.rType == RecordType.srv ? fields.elementAtOrNull(1) : null;
+
+      return (fields.firstOrNull ?? 0, weight ?? 0);
+    }
+
+    final sorted = [...?records];
+    for (final type in [RecordType.mx, RecordType.srv]) {
+      final slots = [
+        for (var i = 0; i < sorted.length; i++)
+          if (sorted[i].rType == type) i,
+      ];
+
+      final ordered = _stableSort(
+        slots,
+        (a, b) => _comparePriority(key(sorted[a]), key(sorted[b])),
+      );
+      final reordered = [for (final slot in ordered) sorted[slot]];
+      for (var i = 0; i < slots.length; i++) {
+        sorted[slots[i]] = reordered[i];
+      }
+    }
+
+    return _Answer(
+      sorted,
+      srvs == null
+          ? null
+          : _stableSort(
+              srvs!,
+              (a, b) => _comparePriority(
+                (a.priority, a.weight),
+                (b.priority, b.weight),
+              ),
+            ),
+    );
+  }
+
+  /// Orders records by ascending priority, then by descending weight.
+  static int _comparePriority((int, int) a, (int, int) b) {
+    final priority = a.$1.compareTo(b.$1);
+
+    return priority != 0 ? priority : b.$2.compareTo(a.$2);
+  }
+
+  /// Returns a sorted copy of [items], keeping ties in their original order.
+  static List<T> _stableSort<T>(
+    List<T> items,
+    int Function(T a, T b) compare,
+  ) {
+    final indices = List.generate(items.length, (index) => index)
+      ..sort((a, b) {
+        final result = compare(items[a], items[b]);
+
+        return result != 0 ? result : a.compareTo(b);
+      });
+
+    return [for (final index in indices) items[index]];
+  }
+
+  /// Returns a copy of this answer with the A and AAAA records cyclically
+  /// rotated by [offset] among their slots, leaving other records in place.
+  _Answer rotated(int offset) {
+    final rotated = [...?records];
+    final slots = [
+      for (var i = 0; i < rotated.length; i++)
+        if (rotated[i].rType == RecordType.A ||
+            rotated[i].rType == RecordType.aaaa)
+          i,
+    ];
+
+    final addresses = [for (final slot in slots) rotated[slot]];
+    for (var i = 0; i < slots.length; i++) {
+      rotated[slots[i]] = addresses[(i + offset) % slots.length];
+    }
+
+    return _Answer(rotated, srvs);
+  }
+
+  /// Returns a copy of this answer with only its first [count] records.
+  _Answer limited(int count) {
+    final kept = [...?records?.take(count)];
+    final srvCount =
+        kept.where((record) => record.rType == RecordType.srv).length;
+
+    return _Answer(kept, srvs?.take(srvCount).toList());
+  }
+
+  /// Returns a copy of this answer without duplicate records, i.e. records
+  /// with the same name, type and data, keeping the first one seen.
+  _Answer deduplicated() {
+    final seen = <String>{};
+    final unique = [
+      for (final record in records ?? const <_Record>[])
+        if (seen.add(
+          '${record.name.toLowerCase()} ${record.typeId} ${record.data}',
+        ))
+          record,
+    ];
+
+    // SRVRecord equality ignores the owner name, so SRV records are keyed the
+    // same way as the records they were parsed from instead.
+    final seenSrvs = <String>{};
+    final uniqueSrvs = srvs
+        ?.where(
+          (srv) => seenSrvs.add(
+            '${srv.fqdn.toLowerCase()} ${srv.priority} ${srv.weight} '
+            '${srv.port} ${srv.target}',
+          ),
+        )
+        .toList();
+
+    return _Answer(unique, uniqueSrvs);
+  }
+
+  /// Returns a copy of this answer with only the records owned by [name],
+  /// compared case-insensitively and regardless of a trailing dot.
+  _Answer ownedBy(String name) {
+    final owner = _fqdn(name).toLowerCase();
+    bool owned(String recordName) => _fqdn(recordName).toLowerCase() == owner;
+
+    return _Answer(
+      records?.where((record) => owned(record.name)).toList(),
+      srvs?.where((srv) => owned(srv.fqdn)).toList(),
+    );
+  }
+
+  /// Follows the CNAME records starting at [name] and returns the final name
+  /// of the chain, or `null` if the chain loops back on itself.
+  String? canonicalName(String name) {
+    final chain = cnameChain(name);
+
+    return chain == null ? null : (chain.isEmpty ? name : chain.last);
+  }
+
+  /// Follows the CNAME records starting at [name] and returns the names it
+  /// leads to, one per hop, or `null` if the chain loops back on itself.
+  ///
+  /// A name holding several CNAME records, which RFC 2181 forbids, is followed
+  /// through the last of them in answer order.
+  List<String>? cnameChain(String name) {
+    String key(String name) =>
+        name.toLowerCase().replaceAll(RegExp(r'\.$'), '');
+
+    final aliases = <String, String>{
+      for (final record in records ?? const <_Record>[])
+        if (record.rType == RecordType.cname) key(record.name): record.data,
+    };
+
+    final chain = <String>[];
+    final visited = <String>{};
+    var current = name;
+    while (aliases.containsKey(key(current))) {
+      if (!visited.add(key(current))) {
+        return null;
+      }
+      current = aliases[key(current)]!;
+      chain.add(current);
+    }
+
+    return chain;
+  }
+
   @override
   String toString() => '''$records''';
 }
 
+/// An enumeration that represents the sections of a DNS response that hold
+/// records.
+enum DNSSection { answer, authority, additional }
+
 class _Record {
   const _Record({
     required this.name,
     required this.rType,
     required this.ttl,
     required this.data,
+    required this.typeId,
+    this.expires,
+    this.section = DNSSection.answer,
   });
 
   final String name;
   final RecordType rType;
+
+  /// The numeric DNS record type id of this record.
+  final int typeId;
   final int ttl;
   final String data;
 
-  factory _Record.fromJson(Map<String, dynamic> json) => _Record(
-        name: json['name'] as String,
+  /// The point in time at which this record goes stale, i.e. the time it was
+  /// received plus its [ttl].
+  ///
+  /// Unlike [ttl], this stays meaningful when the record is stored for later.
+  final DateTime? expires;
+
+  /// The section of the response this record was found in.
+  final DNSSection section;
+
+  /// The presentation name of the record type, e.g. `MX`, or `TYPE<id>` for
+  /// [RecordType.unknown] records.
+  String get typeName => _typeName(rType, typeId);
+
+  factory _Record.fromJson(
+    Map<String, dynamic> json, [
+    DNSSection section = DNSSection.answer,
+  ]) =>
+      _Record(
+        name: _fqdn(json['name'] as String),
         rType: DNSolve.intToRecord(json['type'] as int),
+        typeId: json['type'] as int,
         ttl: json['TTL'] as int,
         data: json['data'] as String,
+        expires: DateTime.now().add(Duration(seconds: json['TTL'] as int)),
+        section: section,
       );
 
   @override
@@ -97,7 +343,7 @@ class _Record {
       buffer.write('\t');
     }
     buffer.writeAll(
-      [ttl, '\tIN\t', rType.name.toUpperCase(), '\t', '"', data, '"'],
+      [ttl, '\tIN\t', typeName, '\t', '"', data, '"'],
     );
 
     return buffer.toString();
@@ -138,6 +384,43 @@ class SRVRecord {
     return records;
   }
 
+  /// Orders a list of [SRVRecord] instances the way clients should attempt
+  /// connections, as specified by RFC 2782.
+  ///
+  /// Records are grouped by ascending priority, and each group is ordered by
+  /// repeated weighted random selection, so that records with a higher weight
+  /// are more likely to come first. Pass a seeded [random] for reproducible
+  /// results.
+  static List<SRVRecord> shuffle(List<SRVRecord> records, [Random? random]) {
+    final generator = random ?? Random();
+    final priorities = records.map((record) => record.priority).toSet().toList()
+      ..sort();
+
+    final ordered = <SRVRecord>[];
+    for (final priority in priorities) {
+      // Zero-weight records go first so they only get picked when the random
+      // draw is 0, as the RFC requires.
+      final group = [
+        ...records.where((r) => r.priority == priority && r.weight == 0),
+        ...records.where((r) => r.priority == priority && r.weight != 0),
+      ];
+
+      while (group.isNotEmpty) {
+        final total = group.fold(0, (sum, record) => sum + record.weight);
+        final draw = generator.nextInt(total + 1);
+
+        var running = 0;
+        final index = group.indexWhere((record) {
+          running += record.weight;
+          return running >= draw;
+        });
+        ordered.add(group.removeAt(index));
+      }
+    }
+
+    return ordered;
+  }
+
   /// Comparator function for sorting [SRVRecord] instances.
   static int _srvRecordSortComparator(SRVRecord a, SRVRecord b) {
     if (a.priority < b.priority) {
@@ -168,9 +451,36 @@ class SRVRecord {
         other.priority == priority &&
         other.weight == weight &&
         other.port == port &&
-        other.target == other.target;
+        other.target == target;
   }
 
   @override
   int get hashCode => Object.hash(priority, weight, port, target);
 }
+
+/// Returns a Uniform Resource Identifier (URI) record (RFC 7553) mapping a
+/// service name to a URI.
+class URIRecord {
+  /// Constructs a [URIRecord] with the specified parameters.
+  const URIRecord({
+    required this.priority,
+    required this.weight,
+    required this.target,
+    required this.fqdn,
+  });
+
+  /// The priority of this URI record.
+  final int priority;
+
+  /// The weight of this URI record.
+  final int weight;
+
+  /// The target URI.
+  final String target;
+
+  /// Fully Qualified Domain Name.
+  final String fqdn;
+
+  @override
+  String toString() => '''$priority $weight "$target"''';
+}
@@ -1,19 +1,37 @@
 part of 'dnsolve.dart';
 
+/// Returns [name] as a fully qualified domain name, with a trailing dot, the
+/// way DNS providers present the names in their answers.
+String _fqdn(String name) => name.endsWith('.') ? name : '$name.';
+
+/// Returns the presentation name of a record type, e.g. `MX`, or `TYPE<id>`
+/// (RFC 3597) for [RecordType.unknown].
+String _typeName(RecordType type, int typeId) =>
+    type == RecordType.unknown ? 'TYPE$typeId' : type.name.toUpperCase();
+
 class _Question {
-  const _Question({required this.name, required this.rType});
+  const _Question({required this.name, required this.rType, this.typeId});
 
   final String? name;
   final RecordType? rType;
 
+  /// The numeric DNS record type id of this question.
+  final int? typeId;
+
+  /// The presentation name of the queried type, e.g. `MX`, or `TYPE<id>` for
+  /// [RecordType.unknown] questions.
+  String? get typeName =>
+      rType == null || typeId == null ? null : _typeName(rType!, typeId!);
+
   factory _Question.fromJson(Map<String, dynamic>? json) {
     if (json == null) {
       return const _Question(name: null, rType: null);
     }
 
     return _Question(
-      name: json['name'] as String,
+      name: _fqdn(json['name'] as String),
       rType: DNSolve.intToRecord(json['type'] as int),
+      typeId: json['type'] as int,
     );
   }
 
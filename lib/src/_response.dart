@@ -27,6 +27,14 @@ class ResolveResponse {
     required this.comment,
     required this.answer,
     required this.questions,
+    this.clientSubnet,
+    this.canonicalName,
+    this.extendedErrors = const [],
+    this.totalAnswers,
+    this.authority,
+    this.additional,
+    this.query,
+    this.partial = false,
   });
 
   /// The status code indicating the result of the DNS resolution.
@@ -56,32 +64,551 @@ class ResolveResponse {
   /// List of questions queried in the resolution request.
   final List<_Question>? questions;
 
+  /// Whether this response merges several lookups of which only some
+  /// succeeded, e.g. a [DNSolve.lookupMulti] where one record type timed out.
+  ///
+  /// The answer of a partial response is incomplete and should not be treated
+  /// as authoritative.
+  final bool partial;
+
+  /// What was actually sent to the provider for this response, to compare
+  /// the requested settings with the flags the provider reported.
+  final QueryInfo? query;
+
+  /// The records of the authority section, e.g. the SOA record of the zone
+  /// when the name or type does not exist.
+  final _Answer? authority;
+
+  /// The records of the additional section.
+  final _Answer? additional;
+
+  /// All records of the response in a single list, answer records first,
+  /// then authority and additional records.
+  ///
+  /// Each record tells the section it belongs to through its `section`.
+  List<_Record> get records => [
+        ...?answer?.records,
+        ...?authority?.records,
+        ...?additional?.records,
+      ];
+
+  /// The EDNS Client Subnet echoed back by the provider, in CIDR notation.
+  ///
+  /// The prefix length of the echoed subnet is the scope the authoritative
+  /// server used when answering, see [clientSubnetScope].
+  final String? clientSubnet;
+
+  /// The final name the queried name resolves to after following the CNAME
+  /// chain in the answer.
+  ///
+  /// This is the queried name itself when the answer holds no CNAME records.
+  /// The intermediate CNAME records are kept in [answer] in chain order.
+  ///
+  /// When a DNAME record redirects a parent of the queried name, the answer
+  /// holds both the DNAME and the CNAME synthesized from it (RFC 6672). The
+  /// chain is followed through the synthesized CNAME.
+  final String? canonicalName;
+
+  /// Extended DNS Errors (RFC 8914) reported by the provider, explaining why
+  /// a lookup failed or was altered.
+  final List<ExtendedDNSError> extendedErrors;
+
+  /// The number of answer records the provider returned, if [answer] was
+  /// capped to fewer records than that with `maxAnswers`, or `null`
+  /// otherwise.
+  final int? totalAnswers;
+
+  /// Whether [answer] only holds the first records of a larger answer, see
+  /// [totalAnswers].
+  ///
+  /// This is an application-level limit, unrelated to DNS truncation ([tc]).
+  bool get truncatedAnswers => totalAnswers != null;
+
+  /// The scope prefix length of the echoed [clientSubnet], or `null` when the
+  /// provider did not echo one.
+  ///
+  /// A scope of `0` means the answer is valid for all clients, i.e. the
+  /// authoritative server did not tailor it to the supplied subnet.
+  int? get clientSubnetScope {
+    final subnet = clientSubnet;
+    if (subnet == null || !subnet.contains('/')) {
+      return null;
+    }
+
+    return int.tryParse(subnet.substring(subnet.lastIndexOf('/') + 1));
+  }
+
   /// Constructs a [ResolveResponse] instance from JSON data.
   ///
   /// The [json] parameter should be a map containing the fields of a DNS
   /// resolution response. Returns a [ResolveResponse] instance with parsed
   /// data.
-  factory ResolveResponse.fromJson(Map<String, dynamic> json) =>
+  factory ResolveResponse.fromJson(Map<String, dynamic> json) {
+    final answer = _Answer.fromJson(json['Answer'] as List<dynamic>?);
+    final questions = json['Question'] == null
+        ? null
+        : (json['Question'] as List<dynamic>)
+            .map(
+              (question) =>
+                  _Question.fromJson(question as Map<String, dynamic>),
+            )
+            .toList();
+
+    var status = json['Status'] as int?;
+    final comments = switch (json['Comment'] ?? json['comment']) {
+      final List<dynamic> list => list.cast<String>(),
+      final String comment => [comment],
+      _ => const <String>[],
+    };
+    var comment = comments.isEmpty ? null : comments.join('; ');
+    String? canonicalName;
+
+    final name = questions?.firstOrNull?.name;
+    if (name != null) {
+      canonicalName = answer.canonicalName(name);
+      if (canonicalName == null) {
+        // SERVFAIL, the chain can never resolve to the requested data.
+        status = 2;
+        comment = [
+          if (comment != null) comment,
+          'CNAME loop detected while resolving $name',
+        ].join('; ');
+      }
+    }
+
+    final response = ResolveResponse(
+      status: status,
+      tc: json['TC'] as bool?,
+      rd: json['RD'] as bool?,
+      ra: json['RA'] as bool?,
+      ad: json['AD'] as bool?,
+      cd: json['CD'] as bool?,
+      comment: comment,
+      answer: answer,
+      questions: questions,
+      clientSubnet: json['edns_client_subnet'] as String?,
+      canonicalName: canonicalName,
+      extendedErrors: ExtendedDNSError._parseAll(comments),
+      authority: json['Authority'] == null
+          ? null
+          : _Answer.fromJson(
+              json['Authority'] as List<dynamic>,
+              DNSSection.authority,
+            ),
+      additional: json['Additional'] == null
+          ? null
+          : _Answer.fromJson(
+              json['Additional'] as List<dynamic>,
+              DNSSection.additional,
+            ),
+    );
+    if (!response.isAliasOnly) {
+      return response;
+    }
+
+    final type = questions!.first.rType!.name.toUpperCase();
+    return response._copyWith(
+      comment: [
+        if (comment != null) comment,
+        '$name is an alias of $canonicalName, which has no $type records',
+      ].join('; '),
+    );
+  }
+
+  /// Whether the queried name exists but is only an alias, i.e. the answer
+  /// holds the CNAME chain to [canonicalName] but no records of the queried
+  /// type.
+  ///
+  /// Unlike `NXDOMAIN`, the name does exist, it is its canonical name that
+  /// lacks the requested data.
+  bool get isAliasOnly {
+    final type = questions?.firstOrNull?.rType;
+    final records = answer?.records ?? const <_Record>[];
+    const aliases = {RecordType.cname, RecordType.dname};
+    if (status != 0 ||
+        records.isEmpty ||
+        type == null ||
+        aliases.contains(type) ||
+        type == RecordType.any ||
+        type == RecordType.unknown) {
+      return false;
+    }
+
+    return records.every((record) => aliases.contains(record.rType));
+  }
+
+  /// Whether the queried name exists but has no records of the queried type,
+  /// i.e. the response is `NOERROR` with an empty answer (`NODATA`).
+  ///
+  /// Unlike `NXDOMAIN` (status 3), this only says that the requested type is
+  /// missing. The [soa] record of the zone tells how long this negative
+  /// answer may be cached.
+  bool get isNoData => status == 0 && (answer?.records?.isEmpty ?? true);
+
+  /// The SOA record from the authority section, which providers include with
+  /// negative answers (`NODATA` and `NXDOMAIN`), if any.
+  _Record? get soa => authority?.records
+      ?.where((record) => record.rType == RecordType.soa)
+      .firstOrNull;
+
+  /// Renders this response in the presentation format of `dig`, with a header
+  /// line, the question section and the non-empty record sections.
+  String get toDig {
+    const rcodes = ['NOERROR', 'FORMERR', 'SERVFAIL', 'NXDOMAIN', 'NOTIMP'];
+    final rcode = switch (status) {
+      final int status when status < rcodes.length => rcodes[status],
+      5 => 'REFUSED',
+      _ => '$status',
+    };
+    final flags = {'tc': tc, 'rd': rd, 'ra': ra, 'ad': ad, 'cd': cd}
+        .entries
+        .where((flag) => flag.value ?? false)
+        .map((flag) => flag.key);
+    final sections = {
+      'ANSWER': answer?.records ?? const <_Record>[],
+      'AUTHORITY': authority?.records ?? const <_Record>[],
+      'ADDITIONAL': additional?.records ?? const <_Record>[],
+    };
+    final counts = sections.entries
+        .map((section) => '${section.key}: ${section.value.length}')
+        .join(', ');
+
+    final buffer = StringBuffer()
+      ..writeln(';; ->>HEADER<<- status: $rcode')
+      ..writeln(
+        ';; flags: ${flags.join(' ')}; '
+        'QUERY: ${questions?.length ?? 0}, $counts',
+      )
+      ..writeln()
+      ..writeln(';; QUESTION SECTION:');
+    for (final question in questions ?? const <_Question>[]) {
+      buffer.writeln(
+        ';${question.name}\t\tIN\t${question.typeName}',
+      );
+    }
+
+    for (final MapEntry(key: section, value: sectionRecords)
+        in sections.entries) {
+      if (sectionRecords.isEmpty) {
+        continue;
+      }
+
+      buffer
+        ..writeln()
+        ..writeln(';; $section SECTION:');
+      for (final record in sectionRecords) {
+        buffer.writeln(
+          '${record.name}\t${record.ttl}\tIN\t'
+          '${record.typeName}\t${record.data}',
+        );
+      }
+    }
+
+    return buffer.toString();
+  }
+
+  /// Returns a copy of this response with the given fields replaced.
+  ResolveResponse _copyWith({
+    int? status,
+    String? comment,
+    _Answer? answer,
+    int? totalAnswers,
+    QueryInfo? query,
+  }) =>
       ResolveResponse(
-        status: json['Status'] as int?,
-        tc: json['TC'] as bool?,
-        rd: json['RD'] as bool?,
-        ra: json['RA'] as bool?,
-        ad: json['AD'] as bool?,
-        cd: json['CD'] as bool?,
-        comment: json['comment'] as String?,
-        answer: _Answer.fromJson(json['Answer'] as List<dynamic>?),
-        questions: json['Question'] == null
-            ? null
-            : (json['Question'] as List<dynamic>)
-                .map(
-                  (question) =>
-                      _Question.fromJson(question as Map<String, dynamic>),
-                )
-                .toList(),
+        status: status ?? this.status,
+        tc: tc,
+        rd: rd,
+        ra: ra,
+        ad: ad,
+        cd: cd,
+        comment: comment ?? this.comment,
+        answer: answer ?? this.answer,
+        questions: questions,
+        clientSubnet: clientSubnet,
+        canonicalName: canonicalName,
+        extendedErrors: extendedErrors,
+        totalAnswers: totalAnswers ?? this.totalAnswers,
+        authority: authority,
+        additional: additional,
+        query: query ?? this.query,
+        partial: partial,
+      );
+
+  /// The category of the failure reported by [status], or `null` if the
+  /// lookup succeeded.
+  ///
+  /// `FORMERR` means the query itself was rejected as malformed, so it is a
+  /// [DNSolveErrorKind.config] failure rather than a server one.
+  DNSolveErrorKind? get errorKind => switch (status) {
+        null || 0 => null,
+        1 => DNSolveErrorKind.config,
+        3 => DNSolveErrorKind.nxdomain,
+        5 => DNSolveErrorKind.refused,
+        _ => DNSolveErrorKind.server,
+      };
+
+  /// The outcome of DNSSEC validation for this response.
+  ///
+  /// Derived from the AD bit, and from Extended DNS Errors on failed lookups.
+  /// When checking was disabled (CD bit set), no validation took place and
+  /// [DNSSECStatus.unchecked] is reported. A `SERVFAIL` without any Extended
+  /// DNS Error is [DNSSECStatus.indeterminate] when DNSSEC records were
+  /// requested, as it may or may not be a validation failure.
+  DNSSECStatus get dnssecStatus {
+    if (cd ?? false) {
+      return DNSSECStatus.unchecked;
+    }
+    if (ad ?? false) {
+      return DNSSECStatus.secure;
+    }
+    if (status != 2) {
+      return DNSSECStatus.insecure;
+    }
+
+    // The INFO-CODEs that report DNSSEC validation failures.
+    const failures = {1, 2, 6, 7, 8, 9, 10, 11, 12};
+    final codes = extendedErrors.map((error) => error.code).toSet();
+    if (codes.any(failures.contains)) {
+      return DNSSECStatus.bogus;
+    }
+    if (codes.contains(5) || (codes.isEmpty && (query?.dnsSec ?? false))) {
+      return DNSSECStatus.indeterminate;
+    }
+
+    return DNSSECStatus.insecure;
+  }
+
+  /// The shortest TTL across all answer records, i.e. how long the whole
+  /// answer may be cached, or `null` if there are no answer records.
+  int? get minTtl {
+    final records = answer?.records ?? const <_Record>[];
+    if (records.isEmpty) {
+      return null;
+    }
+
+    return records.map((record) => record.ttl).reduce((a, b) => a < b ? a : b);
+  }
+
+  /// The IPv4 and IPv6 addresses contained in the answer, in answer order.
+  List<String> get addresses => [
+        for (final record in answer?.records ?? const <_Record>[])
+          if (record.rType == RecordType.A || record.rType == RecordType.aaaa)
+            record.data,
+      ];
+
+  /// Merges the given [responses] into one, concatenating their answers and
+  /// questions.
+  ///
+  /// The merged status is `NOERROR` if any response succeeded. Non-empty
+  /// [failures] are reported in the comment.
+  factory ResolveResponse._merge(
+    List<ResolveResponse> responses,
+    List<String> failures,
+  ) {
+    final first = responses.first;
+    final succeeded = responses.any((response) => response.status == 0);
+
+    final records = <_Record>[];
+    final srvs = <SRVRecord>[];
+    final questions = <_Question>[];
+    final authority = <_Record>[];
+    final additional = <_Record>[];
+    for (final response in responses) {
+      records.addAll(response.answer?.records ?? const []);
+      srvs.addAll(response.answer?.srvs ?? const []);
+      questions.addAll(response.questions ?? const []);
+      authority.addAll(response.authority?.records ?? const []);
+      additional.addAll(response.additional?.records ?? const []);
+    }
+
+    return ResolveResponse(
+      status: succeeded ? 0 : first.status,
+      tc: responses.any((response) => response.tc ?? false),
+      rd: first.rd,
+      ra: first.ra,
+      ad: responses.every((response) => response.ad ?? false),
+      cd: first.cd,
+      comment: failures.isEmpty
+          ? first.comment
+          : 'Partial failure: ${failures.join(', ')}',
+      answer: _Answer(records, srvs),
+      questions: questions,
+      clientSubnet: first.clientSubnet,
+      canonicalName: first.canonicalName,
+      extendedErrors: [
+        for (final response in responses) ...response.extendedErrors,
+      ],
+      authority: authority.isEmpty ? null : _Answer(authority),
+      additional: additional.isEmpty ? null : _Answer(additional),
+      query: first.query,
+      partial: succeeded && failures.isNotEmpty,
+    );
+  }
+
+  @override
+  String toString() =>
+      '''status: $status, truncation: $tc, recursion desired(rd): $rd, recursion available(ra): $ra, authenticated data(ad): $ad, checking disabled(cd): $cd, comment: $comment, answer: $answer, questions: $questions, client subnet: $clientSubnet, canonical name: $canonicalName, extended errors: $extendedErrors''';
+}
+
+/// A summary of a query as it was sent to a DNS provider.
+class QueryInfo {
+  /// Constructs a [QueryInfo] with the specified parameters.
+  const QueryInfo({
+    required this.name,
+    required this.typeId,
+    required this.provider,
+    required this.dnsSec,
+    this.validate = true,
+    this.clientSubnet,
+  });
+
+  /// The queried name, after search domains were applied.
+  final String name;
+
+  /// The numeric DNS record type that was queried.
+  final int typeId;
+
+  /// The provider the query was sent to, which differs from the requested one
+  /// if retries rotated through the providers.
+  final DNSProvider provider;
+
+  /// Whether DNSSEC records were requested.
+  final bool dnsSec;
+
+  /// Whether DNSSEC validation was requested from the provider.
+  final bool validate;
+
+  /// The EDNS Client Subnet that was sent, if any.
+  final String? clientSubnet;
+
+  @override
+  String toString() =>
+      '''(name: $name, type: $typeId, provider: $provider, dnssec: $dnsSec, validate: $validate, client subnet: $clientSubnet)''';
+}
+
+/// An enumeration that represents the outcomes of DNSSEC validation.
+enum DNSSECStatus {
+  /// The answer was validated and is authentic.
+  secure,
+
+  /// The answer is not signed, so it could not be validated.
+  insecure,
+
+  /// Validation failed, e.g. because of invalid or missing signatures.
+  bogus,
+
+  /// Validation could not tell whether the answer is authentic, e.g. the
+  /// lookup failed without saying why while DNSSEC records were requested.
+  indeterminate,
+
+  /// Validation was skipped because checking was disabled.
+  unchecked,
+}
+
+/// An Extended DNS Error (RFC 8914) carrying a machine-readable reason for a
+/// failed or altered resolution.
+///
+/// Providers surface these in the comment of their JSON responses, e.g.
+/// `EDE(6): DNSSEC Bogus`.
+class ExtendedDNSError {
+  /// Constructs an [ExtendedDNSError] with the specified parameters.
+  const ExtendedDNSError({
+    required this.code,
+    required this.purpose,
+    this.extraText,
+  });
+
+  /// The INFO-CODE of the error as registered with IANA.
+  final int code;
+
+  /// The human-readable purpose of the [code], e.g. `DNSSEC Bogus`.
+  final String purpose;
+
+  /// Additional free-form text supplied by the provider, if any.
+  final String? extraText;
+
+  /// Purposes of the INFO-CODEs registered by RFC 8914.
+  static const _purposes = <int, String>{
+    0: 'Other Error',
+    1: 'Unsupported DNSKEY Algorithm',
+    2: 'Unsupported DS Digest Type',
+    3: 'Stale Answer',
+    4: 'Forged Answer',
+    5: 'DNSSEC Indeterminate',
+    6: 'DNSSEC Bogus',
+    7: 'Signature Expired',
+    8: 'Signature Not Yet Valid',
+    9: 'DNSKEY Missing',
+    10: 'RRSIGs Missing',
+    11: 'No Zone Key Bit Set',
+    12: 'NSEC Missing',
+    13: 'Cached Error',
+    14: 'Not Ready',
+    15: 'Blocked',
+    16: 'Censored',
+    17: 'Filtered',
+    18: 'Prohibited',
+    19: 'Stale NXDomain Answer',
+    20: 'Not Authoritative',
+    21: 'Not Supported',
+    22: 'No Reachable Authority',
+    23: 'Network Error',
+    24: 'Invalid Data',
+  };
+
+  /// Extracts every Extended DNS Error mentioned in the given [comments].
+  static List<ExtendedDNSError> _parseAll(List<String> comments) {
+    // Each error runs until the next one, as several may share a comment.
+    final regExp = RegExp(r'EDE\((\d+)\)(.*?)(?=EDE\(\d+\)|$)', dotAll: true);
+
+    return [
+      for (final comment in comments)
+        for (final match in regExp.allMatches(comment))
+          _parse(int.parse(match.group(1)!), match.group(2)!),
+    ];
+  }
+
+  /// Builds the error with the given [code] from the [text] following it in
+  /// a comment, e.g. `: DNSKEY Missing: no SEP found` as Cloudflare writes it
+  /// or `: DNSKEY Missing (no SEP found)` as Google does.
+  ///
+  /// Any text besides the purpose is kept as [extraText], even when it
+  /// replaces the registered purpose.
+  static ExtendedDNSError _parse(int code, String text) {
+    final registered = _purposes[code];
+    final String purpose;
+    var rest = text
+        .replaceFirst(RegExp(r'^\s*:\s*'), '')
+        .replaceFirst(RegExp(r'[\s;,]+$'), '');
+    if (registered != null) {
+      purpose = registered;
+      rest = rest.replaceFirst(
+        RegExp(
+          '^${RegExp.escape(registered)}\\s*(?=[:(]|\$)',
+          caseSensitive: false,
+        ),
+        '',
       );
+    } else {
+      // Unregistered codes are described by the text up to the extra text.
+      final end = rest.indexOf(RegExp('[:(]'));
+      purpose = (end == -1 ? rest : rest.substring(0, end)).trim();
+      rest = end == -1 ? '' : rest.substring(end);
+    }
+
+    rest = rest.replaceFirst(RegExp(r'^\s*:\s*'), '');
+    final wrapped = RegExp(r'^\((.*)\)$', dotAll: true).firstMatch(rest);
+    final extraText = (wrapped?.group(1) ?? rest).trim();
+
+    return ExtendedDNSError(
+      code: code,
+      purpose: purpose,
+      extraText: extraText.isEmpty ? null : extraText,
+    );
+  }
 
   @override
   String toString() =>
-      '''status: $status, truncation: $tc, recursion desired(rd): $rd, recursion available(ra): $ra, authenticated data(ad): $ad, checking disabled(cd): $cd, comment: $comment, answer: $answer, questions: $questions''';
+      '''(code: $code, purpose: $purpose, extra text: $extraText)''';
 }
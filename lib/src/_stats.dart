@@ -0,0 +1,47 @@
+part of 'dnsolve.dart';
+
+/// A snapshot of the queries sent by a [DNSolve] instance, see
+/// [DNSolve.stats].
+///
+/// There is no cache in this package, so every lookup is sent to a provider
+/// and there are no cache hits or misses to report.
+class ResolverStats {
+  /// Constructs a [ResolverStats] with the specified parameters.
+  const ResolverStats({
+    required this.queries,
+    required this.failures,
+    required this.timeouts,
+    required this.answers,
+    required this.statuses,
+    required this.totalLatency,
+  });
+
+  /// The number of queries sent, counting each retry separately.
+  final int queries;
+
+  /// The number of queries that failed without an answer, including
+  /// [timeouts].
+  final int failures;
+
+  /// The number of queries that timed out.
+  final int timeouts;
+
+  /// The number of answers received.
+  final int answers;
+
+  /// The number of answers received per DNS response code, leaving out
+  /// answers that did not carry one.
+  final Map<int, int> statuses;
+
+  /// The time spent waiting for the answers that were received.
+  final Duration totalLatency;
+
+  /// The average time it took a provider to answer, or [Duration.zero] if no
+  /// answer was received yet.
+  Duration get averageLatency =>
+      answers == 0 ? Duration.zero : totalLatency ~/ answers;
+
+  @override
+  String toString() =>
+      '''(queries: $queries, failures: $failures, timeouts: $timeouts, answers: $answers, statuses: $statuses, average latency: $averageLatency)''';
+}
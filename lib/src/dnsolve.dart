@@ -1,4 +1,6 @@
+import 'dart:async';
 import 'dart:convert';
+import 'dart:math';
 
 import 'package:dnsolve/src/exception.dart';
 
@@ -7,47 +9,300 @@ import 'package:http/http.dart' as http;
 part '_answer.dart';
 part '_question.dart';
 part '_response.dart';
+part '_stats.dart';
 
 /// An enumeration that represents various DNS record types.
 enum RecordType {
   A,
   aaaa,
+  afsdb,
   any,
+  apl,
   caa,
   cds,
+  cdnskey,
   cert,
   cname,
+  csync,
+  dhcid,
   dname,
   dnskey,
   ds,
+  eui48,
+  eui64,
   hinfo,
+  hip,
   ipseckey,
+  kx,
+  l32,
+  l64,
+  lp,
+  nid,
   nsec,
   nsec3PARAM,
   naptr,
+  openpgpkey,
   ptr,
   rp,
   rrsig,
+  smimea,
   soa,
+
+  /// Deprecated in favor of [txt] records (RFC 7208), but still found in
+  /// legacy zones. Its data is formatted the same way as TXT data.
   spf,
   srv,
   sshfp,
   tlsa,
+  uri,
   wks,
+  zonemd,
   txt,
   ns,
   mx,
+
+  /// A record type without a dedicated value, e.g. an obsolete type such as
+  /// MD or MINFO. Its numeric id is kept in the record's `typeId`.
+  unknown,
+}
+
+/// An enumeration that represents which address families are resolved when
+/// looking up the addresses of a host.
+enum IPStrategy {
+  /// Only resolve IPv4 (A) addresses.
+  ipv4Only,
+
+  /// Only resolve IPv6 (AAAA) addresses.
+  ipv6Only,
+
+  /// Resolve IPv4 and IPv6 addresses concurrently.
+  ipv4AndIpv6,
+
+  /// Resolve IPv4 addresses, falling back to IPv6 if there are none, or if
+  /// the IPv4 lookup fails.
+  ipv4ThenIpv6,
+
+  /// Resolve IPv6 addresses, falling back to IPv4 if there are none, or if
+  /// the IPv6 lookup fails.
+  ipv6ThenIpv4,
 }
 
+/// A result of [DNSolve.lookupBatchStream]: the `index` of the looked up
+/// domain, and either its `response` or the `error` its lookup failed with.
+typedef BatchLookupEvent = ({
+  int index,
+  ResolveResponse? response,
+  DNSolveException? error,
+});
+
+/// A result of [DNSolve.reverseLookupBatch]: the PTR `records` of an address,
+/// or the `error` its lookup failed with and no records.
+typedef ReverseLookupResult = ({
+  List<_Record> records,
+  DNSolveException? error,
+});
+
 /// An enumeration that represents different DNS service providers.
 enum DNSProvider { google, cloudflare }
 
 class DNSolve {
-  DNSolve() {
-    _client = http.Client();
+  DNSolve({
+    /// How many times a query is attempted before giving up (defaults to 1).
+    ///
+    /// Only transport failures (network errors, timeouts and 5xx responses)
+    /// are retried.
+    /// Must be between 1 and 5.
+    this.attempts = 1,
+
+    /// Whether to rotate through the available [DNSProvider]s between
+    /// attempts instead of retrying the same one (defaults to false).
+    this.rotateProviders = false,
+
+    /// The maximum number of items a batch operation accepts (defaults to
+    /// 10000), guarding against accidentally flooding the providers.
+    this.maxBatchSize = 10000,
+
+    /// Domains appended to names with fewer than [ndots] dots before they are
+    /// looked up, e.g. `corp.example.com` to resolve `intranet`.
+    this.searchDomains = const [],
+
+    /// The number of dots a name must contain to be tried as-is before the
+    /// [searchDomains] are applied (defaults to 1), must not be negative.
+    this.ndots = 1,
+
+    /// How long to wait for a provider to answer a query before giving up
+    /// with a [QueryTimeoutException] (defaults to no timeout).
+    this.queryTimeout,
+
+    /// The HTTP client used to reach the providers.
+    ///
+    /// Connection establishment is handled by this client, so supply one with
+    /// a connection timeout (e.g. an `IOClient` wrapping an `HttpClient` with
+    /// `connectionTimeout` set) to fail fast on unreachable providers
+    /// independently of [queryTimeout].
+    ///
+    /// The same way, queries can be bound to a specific local source address
+    /// on multi-homed hosts, through an `HttpClient.connectionFactory` that
+    /// connects with `Socket.startConnect(..., sourceAddress: ...)`.
+    ///
+    /// A supplied client is never replaced by a default one, so the instance
+    /// cannot be used again after [close] in that case.
+    http.Client? client,
+
+    /// A callback receiving a message for every query sent, answer received
+    /// and failure, to trace what the lookups are doing.
+    this.onLog,
+  })  : _client = client,
+        _customClient = client != null {
+    RangeError.checkValueInInterval(attempts, 1, 5, 'attempts');
+    RangeError.checkNotNegative(ndots, 'ndots');
   }
 
-  late final http.Client _client;
+  /// The HTTP client in use, created on demand so that the instance can be
+  /// used again after [close].
+  http.Client? _client;
+
+  /// Whether [_client] was supplied to the constructor, in which case it is
+  /// not recreated after [close].
+  final bool _customClient;
+
+  /// The number of times this instance was closed, to tell the operations
+  /// started before a [close] apart from those started after it.
+  var _generation = 0;
+
+  /// The version of this package, kept in sync with `pubspec.yaml`.
+  ///
+  /// All [DNSProvider]s are available in every build, so there are no optional
+  /// features to report alongside it.
+  static const version = '1.0.0';
+
+  /// The root name servers and their addresses, as published by IANA in the
+  /// root hints file.
+  ///
+  /// The current list can be looked up with `lookup('.', type: RecordType.ns)`,
+  /// the root domain being a valid name to query.
+  static const rootHints = <({String name, String ipv4, String ipv6})>[
+    (
+      name: 'a.root-servers.net.',
+      ipv4: '198.41.0.4',
+      ipv6: '2001:503:ba3e::2:30',
+    ),
+    (
+      name: 'b.root-servers.net.',
+      ipv4: '170.247.170.2',
+      ipv6: '2801:1b8:10::b',
+    ),
+    (
+      name: 'c.root-servers.net.',
+      ipv4: '192.33.4.12',
+      ipv6: '2001:500:2::c',
+    ),
+    (
+      name: 'd.root-servers.net.',
+      ipv4: '199.7.91.13',
+      ipv6: '2001:500:2d::d',
+    ),
+    (
+      name: 'e.root-servers.net.',
+      ipv4: '192.203.230.10',
+      ipv6: '2001:500:a8::e',
+    ),
+    (
+      name: 'f.root-servers.net.',
+      ipv4: '192.5.5.241',
+      ipv6: '2001:500:2f::f',
+    ),
+    (
+      name: 'g.root-servers.net.',
+      ipv4: '192.112.36.4',
+      ipv6: '2001:500:12::d0d',
+    ),
+    (
+      name: 'h.root-servers.net.',
+      ipv4: '198.97.190.53',
+      ipv6: '2001:500:1::53',
+    ),
+    (
+      name: 'i.root-servers.net.',
+      ipv4: '192.36.148.17',
+      ipv6: '2001:7fe::53',
+    ),
+    (
+      name: 'j.root-servers.net.',
+      ipv4: '192.58.128.30',
+      ipv6: '2001:503:c27::2:30',
+    ),
+    (
+      name: 'k.root-servers.net.',
+      ipv4: '193.0.14.129',
+      ipv6: '2001:7fd::1',
+    ),
+    (
+      name: 'l.root-servers.net.',
+      ipv4: '199.7.83.42',
+      ipv6: '2001:500:9f::42',
+    ),
+    (
+      name: 'm.root-servers.net.',
+      ipv4: '202.12.27.33',
+      ipv6: '2001:dc3::35',
+    ),
+  ];
+
+  /// How many times a query is attempted before giving up.
+  final int attempts;
+
+  /// Whether retries rotate through the available [DNSProvider]s.
+  final bool rotateProviders;
+
+  /// The maximum number of items a batch operation accepts.
+  final int maxBatchSize;
+
+  /// Domains appended to short names before they are looked up.
+  ///
+  /// As lookups go straight to the DNS provider, there is no hosts file that
+  /// could take precedence over the search list.
+  final List<String> searchDomains;
+
+  /// The number of dots a name must contain to be tried as-is first.
+  final int ndots;
+
+  /// How long to wait for a provider to answer a query.
+  final Duration? queryTimeout;
+
+  /// A callback receiving tracing messages about the queries being sent.
+  final void Function(String message)? onLog;
+
+  /// The number of lookups of this instance that rotated their addresses.
+  var _rotation = 0;
+
+  /// The counters reported by [stats].
+  var _queries = 0;
+  var _failures = 0;
+  var _timeouts = 0;
+  var _answers = 0;
+  final _statuses = <int, int>{};
+  var _latency = Duration.zero;
+
+  /// A snapshot of the queries this instance has sent so far.
+  ResolverStats get stats => ResolverStats(
+        queries: _queries,
+        failures: _failures,
+        timeouts: _timeouts,
+        answers: _answers,
+        statuses: Map.unmodifiable(_statuses),
+        totalLatency: _latency,
+      );
+
+  /// Resets the counters reported by [stats].
+  void resetStats() {
+    _queries = 0;
+    _failures = 0;
+    _timeouts = 0;
+    _answers = 0;
+    _statuses.clear();
+    _latency = Duration.zero;
+  }
 
   /// A map that associates [DNSProvider] enum values with their respective DNS
   /// provider URLs.
@@ -60,67 +315,955 @@ class DNSolve {
   Future<ResolveResponse> lookup(
     /// The domain to lookup.
     String domain, {
-    /// Whether to enable DNSSEC (Domain Name System Security Extensions).
+    /// Whether to enable DNSSEC (Domain Name System Security Extensions), i.e.
+    /// set the DO bit so that the RRSIG records are returned with the answer.
     bool dnsSec = false,
 
+    /// Whether the provider should validate the answer with DNSSEC (defaults
+    /// to true), independently of [dnsSec]:
+    ///
+    /// - [dnsSec] and [validate]: validated answer with its signatures.
+    /// - [dnsSec] only: unvalidated answer with its signatures, to inspect
+    ///   them manually. Bogus answers are returned instead of `SERVFAIL`.
+    /// - [validate] only: validated answer without signatures.
+    /// - Neither: unvalidated answer without signatures.
+    ///
+    /// See [ResolveResponse.dnssecStatus] for the outcome.
+    bool validate = true,
+
     /// The DNS record type to look up (defaults to A).
     RecordType type = RecordType.A,
 
     /// The DNS provider to use (defaults to Google).
     DNSProvider provider = DNSProvider.google,
+
+    /// The EDNS Client Subnet to attach to the query, in CIDR notation (e.g.
+    /// `203.0.113.0/24`).
+    ///
+    /// Only honored by providers that support ECS (Google). Cloudflare
+    /// deliberately ignores it for privacy reasons.
+    String? clientSubnet,
+
+    /// Whether to order MX records by preference and SRV records by priority,
+    /// then descending weight, i.e. in the order they should be connected to.
+    bool sort = false,
+
+    /// Whether to drop duplicate records with the same name, type and data.
+    bool dedupe = false,
+
+    /// The numeric DNS record type to look up, overriding [type].
+    ///
+    /// Allows querying types without a [RecordType] value, such as obsolete
+    /// ones. Their data is presented in the generic RFC 3597 hex format.
+    /// Required when [type] is [RecordType.unknown], and must be between 0
+    /// and 65535.
+    int? typeId,
+
+    /// Whether to rotate the A and AAAA records by one more position on each
+    /// such lookup of this instance, for round-robin load distribution.
+    bool rotate = false,
+
+    /// The maximum number of answer records to return, e.g. to only sample
+    /// large round-robin pools (defaults to no limit).
+    ///
+    /// When the answer is capped, [ResolveResponse.truncatedAnswers] is set and
+    /// [ResolveResponse.totalAnswers] holds the original count.
+    int? maxAnswers,
+
+    /// The maximum number of CNAME records to follow (defaults to 10).
+    ///
+    /// Longer chains are reported as `SERVFAIL`, like CNAME loops are.
+    int maxCnameHops = 10,
+
+    /// Whether to only return the answer records owned by the queried name,
+    /// dropping e.g. the CNAME records leading to it and the records of the
+    /// names they point to.
+    ///
+    /// [ResolveResponse.canonicalName] still reports where the chain leads.
+    bool filterName = false,
+
+    /// The wall-clock time by which the lookup must complete, including any
+    /// search domains and retries, e.g. to share a budget across several
+    /// lookups.
+    ///
+    /// Each query waits until the deadline at most, or [queryTimeout] if that
+    /// is shorter. A deadline in the past fails with a
+    /// [QueryTimeoutException] reporting it, without sending any query.
+    DateTime? deadline,
   }) async {
-    assert(domain.isNotEmpty, 'domain should not be empty');
+    final generation = _generation;
+    if (maxAnswers != null) {
+      RangeError.checkNotNegative(maxAnswers, 'maxAnswers');
+    }
+    RangeError.checkNotNegative(maxCnameHops, 'maxCnameHops');
+    if (typeId != null) {
+      RangeError.checkValueInInterval(typeId, 0, 65535, 'typeId');
+    }
+    if (type == RecordType.unknown && typeId == null) {
+      throw ArgumentError.value(
+        type,
+        'type',
+        'Unknown record types can only be looked up by their typeId',
+      );
+    }
+    final name = domain.trim();
+    _validateDomain(name);
+    _checkDeadline(deadline);
 
+    final queriedType = typeId ?? _typeToInt(type);
     final queryParams = <String, String>{};
     queryParams
-      ..putIfAbsent('name', () => domain)
-      ..putIfAbsent('type', () => _typeToInt(type).toString())
-      ..putIfAbsent('dnssec', () => dnsSec.toString());
+      ..putIfAbsent('type', () => queriedType.toString())
+      ..putIfAbsent('do', () => dnsSec.toString());
+    if (!validate) {
+      queryParams.putIfAbsent('cd', () => 'true');
+    }
+    if (clientSubnet != null) {
+      queryParams.putIfAbsent('edns_client_subnet', () => clientSubnet);
+    }
 
-    final headers = <String, String>{'Accept': 'application/dns-json'};
-    final url = _dnsProviders[provider] ?? 'https://dns.google.com/resolve';
+    late ResolveResponse response;
+    for (final candidate in _searchList(name)) {
+      response = await _query(
+        {...queryParams, 'name': candidate},
+        provider,
+        deadline: deadline,
+        generation: generation,
+      );
+      // Move on to the next candidate only if this name does not exist.
+      if (response.status != 3) {
+        break;
+      }
+    }
+
+    final queried = response.questions?.firstOrNull?.name;
+    final hops = queried == null ? null : response.answer?.cnameChain(queried);
+    if (hops != null && hops.length > maxCnameHops) {
+      response = response._copyWith(
+        status: 2,
+        comment: 'CNAME chain exceeded $maxCnameHops hops',
+      );
+    }
+    if (queriedType == _records[RecordType.any]) {
+      response = _explainAny(response);
+    }
+    if (filterName && queried != null && response.answer != null) {
+      response = response._copyWith(answer: response.answer!.ownedBy(queried));
+    }
+    if (dedupe && response.answer != null) {
+      response = response._copyWith(answer: response.answer!.deduplicated());
+    }
+    if (sort && response.answer != null) {
+      response = response._copyWith(answer: response.answer!.sorted());
+    }
+    if (rotate && response.answer != null) {
+      response =
+          response._copyWith(answer: response.answer!.rotated(_rotation++));
+    }
+    final total = response.answer?.records?.length ?? 0;
+    if (maxAnswers != null && total > maxAnswers) {
+      response = response._copyWith(
+        answer: response.answer!.limited(maxAnswers),
+        totalAnswers: total,
+      );
+    }
+
+    return response;
+  }
+
+  /// Adds a comment to an ANY [response] that was refused or minimized as
+  /// allowed by RFC 8482, so it doesn't look like a plain failure.
+  static ResolveResponse _explainAny(ResolveResponse response) {
+    final records = response.answer?.records ?? const <_Record>[];
+    final minimal = records.length == 1 &&
+        records.single.rType == RecordType.hinfo &&
+        records.single.data.contains('RFC8482');
+
+    if (!minimal && response.status != 5) {
+      return response;
+    }
+
+    const note = 'ANY queries are refused or answered minimally by this '
+        'provider (RFC 8482), query specific record types instead';
+
+    return response._copyWith(
+      comment: [if (response.comment != null) response.comment, note]
+          .join('; '),
+    );
+  }
+
+  /// Returns the fully qualified names to try, in order, when looking up
+  /// [domain].
+  ///
+  /// Fully qualified names (with a trailing dot) are used as-is. Otherwise the
+  /// [searchDomains] are applied, after the name itself if it contains at
+  /// least [ndots] dots, or before it if it does not.
+  List<String> _searchList(String domain) {
+    if (domain.endsWith('.')) {
+      return [domain];
+    }
+
+    final searched = [
+      for (final search in searchDomains)
+        '$domain.${search.replaceAll(RegExp(r'^\.|\.$'), '')}.',
+    ];
+
+    return '.'.allMatches(domain).length >= ndots
+        ? ['$domain.', ...searched]
+        : [...searched, '$domain.'];
+  }
+
+  /// Throws a [DomainFormatException] if [domain] cannot be a valid domain
+  /// name, so that it is rejected before reaching the DNS provider.
+  ///
+  /// The root domain (`.`) is valid, as querying e.g. its NS records is
+  /// legitimate.
+  static void _validateDomain(String domain) {
+    if (domain == '.') {
+      return;
+    }
+
+    final name = domain.endsWith('.')
+        ? domain.substring(0, domain.length - 1)
+        : domain;
+    if (name.isEmpty) {
+      throw const DomainFormatException('Domain name should not be empty');
+    }
+    if (utf8.encode(name).length > 253) {
+      throw DomainFormatException(
+        'Domain name "$domain" exceeds 253 octets',
+      );
+    }
+
+    for (final label in name.split('.')) {
+      if (label.isEmpty) {
+        throw DomainFormatException(
+          'Domain name "$domain" contains an empty label',
+        );
+      }
+      if (utf8.encode(label).length > 63) {
+        throw DomainFormatException('Label "$label" exceeds 63 octets');
+      }
+    }
+  }
+
+  /// Throws a [QueryTimeoutException] if [deadline] has already passed, so
+  /// that no query is sent.
+  static void _checkDeadline(DateTime? deadline) {
+    if (deadline != null && !deadline.isAfter(DateTime.now())) {
+      throw QueryTimeoutException(Duration.zero, deadline: deadline);
+    }
+  }
+
+  /// Performs DNS lookups for several record types of the given domain
+  /// concurrently and merges their answers into a single response.
+  ///
+  /// The resulting status is `NOERROR` (0) if any of the lookups succeeded.
+  /// Lookups that failed are described in the response's comment, in the
+  /// order of [types]. If all of them failed, the error of the first type is
+  /// thrown.
+  Future<ResolveResponse> lookupMulti(
+    /// The domain to lookup.
+    String domain, {
+    /// The DNS record types to look up (defaults to A and AAAA), must not be
+    /// empty.
+    List<RecordType> types = const [RecordType.A, RecordType.aaaa],
+
+    /// Whether to enable DNSSEC (Domain Name System Security Extensions).
+    bool dnsSec = false,
+
+    /// Whether the provider should validate the answers with DNSSEC (defaults
+    /// to true).
+    bool validate = true,
+
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// Whether to drop duplicate records, such as the CNAME records shared by
+    /// the answers of the different types.
+    bool dedupe = false,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) async {
+    if (types.isEmpty) {
+      throw ArgumentError.value(types, 'types', 'must not be empty');
+    }
+
+    final errors = List<DNSolveException?>.filled(types.length, null);
+    final results = await Future.wait(
+      List.generate(types.length, (index) async {
+        try {
+          return await lookup(
+            domain,
+            dnsSec: dnsSec,
+            validate: validate,
+            type: types[index],
+            provider: provider,
+            deadline: deadline,
+          );
+        } on DNSolveException catch (e) {
+          errors[index] = e;
+          return null;
+        }
+      }),
+    );
+
+    final responses = results.whereType<ResolveResponse>().toList();
+    if (responses.isEmpty) {
+      // Report the failure of the first type, whichever lookup failed first.
+      throw errors.first!;
+    }
+
+    final failures = <String>[];
+    for (var i = 0; i < types.length; i++) {
+      final type = types[i].name.toUpperCase();
+      final status = results[i]?.status;
+      if (errors[i] case final error?) {
+        failures.add('$type ($error)');
+      } else if (status != null && status != 0) {
+        failures.add('$type (status: $status)');
+      }
+    }
+
+    final merged = ResolveResponse._merge(responses, failures);
+    if (dedupe && merged.answer != null) {
+      return merged._copyWith(answer: merged.answer!.deduplicated());
+    }
+
+    return merged;
+  }
+
+  /// Performs DNS lookups for all of the given domains concurrently, emitting
+  /// each response as soon as its lookup completes.
+  ///
+  /// Every event carries the index of its domain in [domains], as responses
+  /// arrive out of order, along with either the response or the error of a
+  /// failed lookup. Failures don't interrupt the other lookups, and the
+  /// stream closes once all lookups are done, or after an error event with a
+  /// [ClientClosedException] if [close] is called meanwhile. Cancelling the
+  /// subscription stops the batch: lookups in flight complete unreported and
+  /// the remaining ones are not sent.
+  Stream<BatchLookupEvent> lookupBatchStream(
+    /// The domains to lookup, at most [maxBatchSize].
+    List<String> domains, {
+    /// The DNS record type to look up (defaults to A).
+    RecordType type = RecordType.A,
+
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// The maximum number of lookups in flight at once (defaults to 50).
+    int concurrency = defaultConcurrency,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) {
+    RangeError.checkValueInInterval(
+      concurrency,
+      1,
+      maxConcurrency,
+      'concurrency',
+    );
+
+    _checkBatchSize(domains.length, 'domains');
+
+    final controller = StreamController<BatchLookupEvent>();
+    final generation = _generation;
+    var cancelled = false;
+
+    Future<void> run() async {
+      final indices = List.generate(domains.length, (index) => index);
+      try {
+        await _mapBounded(indices, concurrency, (index) async {
+          _checkOpen(generation);
+          // Nobody listens anymore, so the remaining lookups are skipped.
+          if (cancelled) {
+            return;
+          }
+          try {
+            final response = await lookup(
+              domains[index],
+              type: type,
+              provider: provider,
+              deadline: deadline,
+            );
+            controller.add((index: index, response: response, error: null));
+          } on ClientClosedException {
+            rethrow;
+          } on DNSolveException catch (error) {
+            controller.add((index: index, response: null, error: error));
+          }
+        });
+      } on ClientClosedException catch (error, stackTrace) {
+        controller.addError(error, stackTrace);
+      } finally {
+        await controller.close();
+      }
+    }
+
+    controller
+      ..onListen = () => unawaited(run())
+      ..onCancel = () {
+        cancelled = true;
+      };
+
+    return controller.stream;
+  }
+
+  /// Resolves both the IPv4 and IPv6 addresses of the given host.
+  ///
+  /// This is a convenience over [lookupMulti] for the common case of just
+  /// wanting to connect to a host. CNAMEs are followed by the provider and kept
+  /// once in the answer alongside the A and AAAA records, so TTLs and the
+  /// canonical name chain are preserved. The addresses are available through
+  /// [ResolveResponse.addresses].
+  Future<ResolveResponse> lookupIps(
+    /// The host to resolve.
+    String host, {
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// Which address families to resolve (defaults to both at once).
+    ///
+    /// This only affects this method, explicit A or AAAA lookups through
+    /// [lookup] are always sent as requested. When falling back and neither
+    /// family has addresses, the response or failure of the preferred family
+    /// is reported.
+    IPStrategy strategy = IPStrategy.ipv4AndIpv6,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) async {
+    final families = switch (strategy) {
+      IPStrategy.ipv4Only => [RecordType.A],
+      IPStrategy.ipv6Only => [RecordType.aaaa],
+      IPStrategy.ipv4AndIpv6 => [RecordType.A, RecordType.aaaa],
+      IPStrategy.ipv4ThenIpv6 => [RecordType.A],
+      IPStrategy.ipv6ThenIpv4 => [RecordType.aaaa],
+    };
+    final fallback = switch (strategy) {
+      IPStrategy.ipv4ThenIpv6 => RecordType.aaaa,
+      IPStrategy.ipv6ThenIpv4 => RecordType.A,
+      _ => null,
+    };
+
+    Future<ResolveResponse> resolve(List<RecordType> types) => lookupMulti(
+          host,
+          types: types,
+          provider: provider,
+          dedupe: true,
+          deadline: deadline,
+        );
+
+    if (fallback == null) {
+      return resolve(families);
+    }
+
+    // The preferred family is given up on when it has no addresses, whether
+    // it answered none, answered with a failure status or failed outright.
+    ResolveResponse? response;
+    DNSolveException? error;
+    try {
+      response = await resolve(families);
+      if (response.status == 0 && response.addresses.isNotEmpty) {
+        return response;
+      }
+    } on ClientClosedException {
+      rethrow;
+    } on DNSolveException catch (e) {
+      error = e;
+    }
 
-    final body =
-        await _get(url, queryParameters: queryParams, headers: headers);
+    try {
+      final fallbackResponse = await resolve([fallback]);
+      if (fallbackResponse.addresses.isNotEmpty) {
+        return fallbackResponse;
+      }
+    } on ClientClosedException {
+      rethrow;
+    } on DNSolveException {
+      // Reported below as the outcome of the preferred family.
+    }
+
+    // Neither family has addresses, so the preferred one is reported.
+    if (error != null) {
+      throw error;
+    }
+
+    return response!;
+  }
+
+  /// Resolves the IPv4 and IPv6 addresses of the given host and returns them
+  /// in the order connections should be attempted in.
+  ///
+  /// Following the Happy Eyeballs guidance of RFC 8305, address families are
+  /// interleaved starting with IPv6 (AAAA, A, AAAA, ...). The family of each
+  /// address is given by its record type, and its TTL is preserved.
+  Future<List<_Record>> lookupHappyEyeballs(
+    /// The host to resolve.
+    String host, {
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) async {
+    final response =
+        await lookupIps(host, provider: provider, deadline: deadline);
+    final records = response.answer?.records ?? const <_Record>[];
+    final ipv6 = records.where((record) => record.rType == RecordType.aaaa);
+    final ipv4 = records.where((record) => record.rType == RecordType.A);
+
+    final interleaved = <_Record>[];
+    final iterators = [ipv6.iterator, ipv4.iterator];
+    var exhausted = 0;
+    while (exhausted < iterators.length) {
+      exhausted = 0;
+      for (final iterator in iterators) {
+        if (iterator.moveNext()) {
+          interleaved.add(iterator.current);
+        } else {
+          exhausted++;
+        }
+      }
+    }
 
-    return ResolveResponse.fromJson(json.decode(body) as Map<String, dynamic>);
+    return interleaved;
   }
 
   /// Performs a reverse DNS lookup for the given IP address.
+  ///
+  /// IPv6 addresses may be given in compressed form, e.g. `2001:db8::1`.
+  /// Anything that is not a valid IPv4 or IPv6 address, e.g. `999.1.1`, throws
+  /// an [AddressFormatException] without being queried.
   Future<List<_Record>> reverseLookup(
     /// The IP address to perform a reverse lookup for.
     String ip, {
     /// THE DNS provider to use (defaults to Google).
     DNSProvider provider = DNSProvider.google,
+
+    /// Whether to sort the PTR records by name, for results that are stable
+    /// across runs when an address has several names.
+    bool sort = false,
+
+    /// The wall-clock time by which the lookup must complete, as for
+    /// [lookup].
+    DateTime? deadline,
   }) async {
-    final queryParams = <String, String>{};
-    String? reverse() {
-      if (ip.contains('.')) {
-        return '${ip.split('.').reversed.join('.')}.in-addr.arpa';
-      } else if (ip.contains(':')) {
-        return '${ip.split(':').join().split('').reversed.join('.')}.ip6.arpa';
-      } else {
-        return null;
-      }
+    _checkDeadline(deadline);
+    final List<int> bytes;
+    try {
+      bytes = ip.contains(':')
+          ? Uri.parseIPv6Address(ip)
+          : Uri.parseIPv4Address(ip);
+    } on FormatException {
+      throw AddressFormatException('Invalid IP address "$ip"');
     }
 
-    final reversed = reverse();
-    if (reversed == null) {
-      return [];
+    final String reversed;
+    if (bytes.length == 16) {
+      final nibbles = [
+        for (final byte in bytes.reversed) ...[byte & 0xf, byte >> 4],
+      ];
+      reversed =
+          '${nibbles.map((nibble) => nibble.toRadixString(16)).join('.')}'
+          '.ip6.arpa';
+    } else {
+      reversed = '${bytes.reversed.join('.')}.in-addr.arpa';
     }
 
+    final queryParams = <String, String>{};
     queryParams
       ..putIfAbsent('name', () => reversed)
       ..putIfAbsent('type', () => _records[RecordType.ptr]!.toString());
 
+    final response = await _query(queryParams, provider, deadline: deadline);
+    final records = response.answer!.records ?? [];
+    if (!sort) {
+      return records;
+    }
+
+    final names = records.where((record) => record.rType == RecordType.ptr);
+    return [
+      ...records.where((record) => record.rType != RecordType.ptr),
+      ...names.toList()
+        ..sort((a, b) => a.data.toLowerCase().compareTo(b.data.toLowerCase())),
+    ];
+  }
+
+  /// Performs reverse DNS lookups for all of the given IP addresses
+  /// concurrently, IPv4 and IPv6 alike.
+  ///
+  /// The results are returned in the same order as [ips]. Failures are
+  /// isolated per address: a failed lookup or a malformed address yields
+  /// no records and the error it failed with, without affecting the others.
+  /// An address without PTR records yields no records and no error.
+  Future<List<ReverseLookupResult>> reverseLookupBatch(
+    /// The IP addresses to perform reverse lookups for, at most
+    /// [maxBatchSize].
+    List<String> ips, {
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// The maximum number of lookups in flight at once (defaults to 50).
+    ///
+    /// Must be between 1 and [maxConcurrency]; keeping it low avoids being
+    /// rate-limited by the provider.
+    int concurrency = defaultConcurrency,
+
+    /// Whether to sort the PTR records of each address by name.
+    bool sort = false,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) {
+    RangeError.checkValueInInterval(
+      concurrency,
+      1,
+      maxConcurrency,
+      'concurrency',
+    );
+
+    _checkBatchSize(ips.length, 'ips');
+
+    final generation = _generation;
+    return _mapBounded(ips, concurrency, (ip) async {
+      _checkOpen(generation);
+      try {
+        final records = await reverseLookup(
+          ip,
+          provider: provider,
+          sort: sort,
+          deadline: deadline,
+        );
+        return (records: records, error: null);
+      } on ClientClosedException {
+        rethrow;
+      } on DNSolveException catch (error) {
+        return (records: const <_Record>[], error: error);
+      }
+    });
+  }
+
+  /// Performs reverse DNS lookups for every host address in the given CIDR
+  /// range, e.g. `192.0.2.0/24`.
+  ///
+  /// Returns the results keyed by address, in address order, see
+  /// [reverseLookupBatch] for how failures are reported. The network
+  /// and broadcast addresses of IPv4 ranges are skipped. To avoid accidental
+  /// huge enumerations, ranges larger than /20 for IPv4 or /116 for IPv6 are
+  /// rejected with an [AddressFormatException].
+  Future<Map<String, ReverseLookupResult>> reverseLookupCidr(
+    /// The CIDR range to perform reverse lookups for.
+    String cidr, {
+    /// The DNS provider to use (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+
+    /// The maximum number of lookups in flight at once (defaults to 50).
+    int concurrency = defaultConcurrency,
+
+    /// Whether to sort the PTR records of each address by name.
+    bool sort = false,
+
+    /// The wall-clock time by which the lookups must complete, as for
+    /// [lookup].
+    DateTime? deadline,
+  }) async {
+    final addresses = _enumerateCidr(cidr);
+    final results = await reverseLookupBatch(
+      addresses,
+      provider: provider,
+      concurrency: concurrency,
+      sort: sort,
+      deadline: deadline,
+    );
+
+    return Map.fromIterables(addresses, results);
+  }
+
+  /// The default number of queries a batch operation keeps in flight.
+  static const defaultConcurrency = 50;
+
+  /// The maximum number of queries a batch operation may keep in flight.
+  static const maxConcurrency = 500;
+
+  /// Throws a [RangeError] if a batch of [count] items exceeds
+  /// [maxBatchSize].
+  void _checkBatchSize(int count, String name) {
+    if (count > maxBatchSize) {
+      throw RangeError.value(
+        count,
+        name,
+        'Batch of $count items exceeds the maximum of $maxBatchSize',
+      );
+    }
+  }
+
+  /// Applies [convert] to all of the [items], running at most [concurrency]
+  /// conversions at a time, and returns the results in the order of [items].
+  ///
+  /// If a conversion throws, the remaining items are skipped and the error is
+  /// rethrown once the conversions in flight are done.
+  static Future<List<R>> _mapBounded<T, R>(
+    List<T> items,
+    int concurrency,
+    Future<R> Function(T item) convert,
+  ) async {
+    final results = List<R?>.filled(items.length, null);
+    var next = 0;
+
+    Future<void> worker() async {
+      while (next < items.length) {
+        final index = next++;
+        try {
+          results[index] = await convert(items[index]);
+        } on Object {
+          next = items.length;
+          rethrow;
+        }
+      }
+    }
+
+    await Future.wait(
+      List.generate(
+        concurrency < items.length ? concurrency : items.length,
+        (_) => worker(),
+      ),
+    );
+
+    return results.cast<R>();
+  }
+
+  /// Lists the host addresses of the given [cidr] range.
+  static List<String> _enumerateCidr(String cidr) {
+    final parts = cidr.split('/');
+    final prefix = parts.length == 2 ? int.tryParse(parts[1]) : null;
+    final isIPv6 = parts.first.contains(':');
+
+    late final List<int> bytes;
+    try {
+      bytes = isIPv6
+          ? Uri.parseIPv6Address(parts.first)
+          : Uri.parseIPv4Address(parts.first);
+    } on FormatException {
+      throw AddressFormatException('Invalid CIDR range "$cidr"');
+    }
+    if (prefix == null || prefix < 0 || prefix > bytes.length * 8) {
+      throw AddressFormatException('Invalid CIDR range "$cidr"');
+    }
+
+    final minPrefix = isIPv6 ? 116 : 20;
+    if (prefix < minPrefix) {
+      throw AddressFormatException(
+        'CIDR range "$cidr" is larger than the allowed /$minPrefix',
+      );
+    }
+
+    // At most 12 host bits are left, so only the last two bytes vary.
+    final hostBits = bytes.length * 8 - prefix;
+    final base = ((bytes[bytes.length - 2] << 8) | bytes.last) &
+        ~((1 << hostBits) - 1) &
+        0xffff;
+    final count = 1 << hostBits;
+    final skipEdges = !isIPv6 && hostBits > 1;
+
+    final addresses = <String>[];
+    for (var i = skipEdges ? 1 : 0; i < (skipEdges ? count - 1 : count); i++) {
+      final value = base + i;
+      final host = [
+        ...bytes.sublist(0, bytes.length - 2),
+        value >> 8,
+        value & 0xff,
+      ];
+
+      if (isIPv6) {
+        addresses.add(
+          [
+            for (var j = 0; j < host.length; j += 2)
+              ((host[j] << 8) | host[j + 1]).toRadixString(16).padLeft(4, '0'),
+          ].join(':'),
+        );
+      } else {
+        addresses.add(host.join('.'));
+      }
+    }
+
+    return addresses;
+  }
+
+  /// Checks whether the given DNS provider is answering and measures its
+  /// round-trip time.
+  ///
+  /// Sends a single lightweight query for the NS records of the root domain,
+  /// without retries, so that the measured time is that of one round trip.
+  /// Throws a [DNSolveException] if the provider cannot be reached or fails to
+  /// answer, within [queryTimeout] if set, or a [ResponseStatusException] if
+  /// it answers with a failure status.
+  Future<Duration> ping({
+    /// The DNS provider to check (defaults to Google).
+    DNSProvider provider = DNSProvider.google,
+  }) async {
+    final stopwatch = Stopwatch()..start();
+    final response = await _query(
+      {'name': '.', 'type': _records[RecordType.ns]!.toString()},
+      provider,
+      retry: false,
+    );
+    stopwatch.stop();
+
+    if (response.status != 0) {
+      throw ResponseStatusException(
+        response.status,
+        'Provider ${provider.name} answered with status ${response.status}',
+      );
+    }
+
+    return stopwatch.elapsed;
+  }
+
+  /// Closes the underlying HTTP client, aborting any in-flight queries.
+  ///
+  /// Pending lookups complete with a [ClientClosedException] and are not
+  /// retried, and batch operations stop without starting their remaining
+  /// lookups. Lookups started after closing use a new default HTTP client,
+  /// unless a client was supplied to the constructor, in which case they fail
+  /// with a [ClientClosedException].
+  void close() {
+    _generation++;
+    _client?.close();
+    _client = null;
+  }
+
+  /// Throws a [ClientClosedException] if this instance was closed since the
+  /// operation started at [generation].
+  void _checkOpen(int generation) {
+    if (generation != _generation) {
+      throw const ClientClosedException('The lookup was aborted by close()');
+    }
+  }
+
+  /// Sends a DNS query with the given [queryParams] to the [provider] and
+  /// parses its JSON response.
+  ///
+  /// Transport failures and timeouts are retried until [attempts] is
+  /// exhausted, moving on to the next provider on each retry if
+  /// [rotateProviders] is set. Once a retried query fails for good, be it
+  /// because [attempts] is exhausted, the [deadline] passed or the last
+  /// failure is not transient, an [AttemptsExhaustedException] reports each
+  /// of the failures.
+  ///
+  /// Retries stop once the [deadline], if any, has passed, and no attempt is
+  /// made once this instance was closed since [generation]. Without [retry],
+  /// a single attempt is made.
+  Future<ResolveResponse> _query(
+    Map<String, String> queryParams,
+    DNSProvider provider, {
+    DateTime? deadline,
+    int? generation,
+    bool retry = true,
+  }) async {
     final headers = <String, String>{'Accept': 'application/dns-json'};
-    final url = _dnsProviders[provider] ?? 'https://dns.google.com/resolve';
+    const providers = DNSProvider.values;
+    final failures = <(String, DNSolveException)>[];
+    final started = generation ?? _generation;
+    // An expired deadline fails before anything is sent, so it is not counted
+    // as a query in the stats.
+    _checkDeadline(deadline);
 
-    final body =
-        await _get(url, queryParameters: queryParams, headers: headers);
-    final response =
-        ResolveResponse.fromJson(json.decode(body) as Map<String, dynamic>);
-    return response.answer!.records ?? [];
+    for (var attempt = 1;; attempt++) {
+      _checkOpen(started);
+      final current = rotateProviders
+          ? providers[(provider.index + attempt - 1) % providers.length]
+          : provider;
+      final url = _dnsProviders[current] ?? 'https://dns.google.com/resolve';
+
+      onLog?.call(
+        'Querying ${current.name} for ${queryParams['name']} '
+        '(type ${queryParams['type']}, attempt $attempt of $attempts)',
+      );
+
+      _queries++;
+      final stopwatch = Stopwatch()..start();
+      try {
+        final body = await _get(
+          url,
+          queryParameters: queryParams,
+          headers: headers,
+          deadline: deadline,
+        );
+        final response = _parse(body)._copyWith(
+          query: QueryInfo(
+            name: queryParams['name']!,
+            typeId: int.parse(queryParams['type']!),
+            provider: current,
+            dnsSec: queryParams['do'] == 'true',
+            validate: queryParams['cd'] != 'true',
+            clientSubnet: queryParams['edns_client_subnet'],
+          ),
+        );
+        _answers++;
+        _latency += stopwatch.elapsed;
+        if (response.status case final status?) {
+          _statuses.update(status, (count) => count + 1, ifAbsent: () => 1);
+        }
+        onLog?.call('${current.name} answered with status ${response.status}');
+
+        return response;
+      } on DNSolveException catch (error) {
+        _failures++;
+        if (error is QueryTimeoutException) {
+          _timeouts++;
+        }
+        onLog?.call('${current.name} failed: $error');
+        // The client was closed under this query, which aborted it.
+        _checkOpen(started);
+
+        final transient = switch (error) {
+          NetworkException() || QueryTimeoutException() => true,
+          ResponseException(:final statusCode) => statusCode >= 500,
+          _ => false,
+        };
+        final expired = deadline != null && !deadline.isAfter(DateTime.now());
+        failures.add((current.name, error));
+        if (!transient || !retry || expired || attempt >= attempts) {
+          // The failure of a single attempt is thrown as is.
+          if (failures.length == 1) {
+            rethrow;
+          }
+          throw AttemptsExhaustedException(failures);
+        }
+      }
+    }
+  }
+
+  /// Parses the JSON [body] of a provider response, throwing a
+  /// [ResponseFormatException] if it is not a JSON object of the expected
+  /// shape, e.g. with a missing `TTL` or a string `Status`.
+  static ResolveResponse _parse(String body) {
+    final Object? decoded;
+    try {
+      decoded = json.decode(body);
+    } on FormatException catch (error) {
+      throw ResponseFormatException(error.message);
+    }
+
+    if (decoded is! Map<String, dynamic>) {
+      throw const ResponseFormatException('Expected a JSON object');
+    }
+
+    try {
+      return ResolveResponse.fromJson(decoded);
+      // The fields are read with casts, which throw on unexpected types.
+      // ignore: avoid_catching_errors
+    } on TypeError catch (error) {
+      throw ResponseFormatException('Unexpected response structure: $error');
+    }
   }
 
   /// Sends an HTTP GET request to the specified URL with optional query
@@ -129,6 +1272,7 @@ class DNSolve {
     String url, {
     Map<String, String>? queryParameters,
     Map<String, String>? headers,
+    DateTime? deadline,
   }) async {
     late Uri uri;
     {
@@ -139,7 +1283,35 @@ class DNSolve {
       }
     }
 
-    final response = await _client.get(uri, headers: headers);
+    var timeout = queryTimeout;
+    DateTime? limit;
+    if (deadline != null) {
+      final remaining = deadline.difference(DateTime.now());
+      if (remaining <= Duration.zero) {
+        throw QueryTimeoutException(Duration.zero, deadline: deadline);
+      }
+      if (timeout == null || remaining < timeout) {
+        timeout = remaining;
+        limit = deadline;
+      }
+    }
+
+    if (_client == null && _customClient) {
+      throw const ClientClosedException(
+        'The HTTP client supplied to DNSolve was closed',
+      );
+    }
+
+    late http.Response response;
+    try {
+      final request = (_client ??= http.Client()).get(uri, headers: headers);
+      response = await (timeout == null ? request : request.timeout(timeout));
+    } on http.ClientException catch (error) {
+      throw NetworkException(error.message);
+    } on TimeoutException {
+      throw QueryTimeoutException(timeout!, deadline: limit);
+    }
+
     return _handleResponse(response);
   }
 
@@ -148,38 +1320,59 @@ class DNSolve {
   static const _records = {
     RecordType.A: 1,
     RecordType.aaaa: 28,
+    RecordType.afsdb: 18,
     RecordType.any: 255,
+    RecordType.apl: 42,
     RecordType.caa: 257,
+    RecordType.cdnskey: 60,
     RecordType.cds: 59,
     RecordType.cert: 37,
     RecordType.cname: 5,
+    RecordType.csync: 62,
+    RecordType.dhcid: 49,
     RecordType.dname: 39,
     RecordType.dnskey: 48,
     RecordType.ds: 43,
+    RecordType.eui48: 108,
+    RecordType.eui64: 109,
     RecordType.hinfo: 13,
+    RecordType.hip: 55,
     RecordType.ipseckey: 45,
+    RecordType.kx: 36,
+    RecordType.l32: 105,
+    RecordType.l64: 106,
+    RecordType.lp: 107,
     RecordType.mx: 15,
     RecordType.naptr: 35,
+    RecordType.nid: 104,
     RecordType.ns: 2,
     RecordType.nsec: 47,
     RecordType.nsec3PARAM: 51,
+    RecordType.openpgpkey: 61,
     RecordType.ptr: 12,
     RecordType.rp: 17,
     RecordType.rrsig: 46,
+    RecordType.smimea: 53,
     RecordType.soa: 6,
     RecordType.spf: 99,
     RecordType.srv: 33,
     RecordType.sshfp: 44,
     RecordType.tlsa: 52,
     RecordType.txt: 16,
+    RecordType.uri: 256,
     RecordType.wks: 11,
+    RecordType.zonemd: 63,
   };
 
+  /// The record types supported by [lookup], mapped to their numeric DNS
+  /// record type ids.
+  static Map<RecordType, int> get supportedRecordTypes => _records;
+
   /// Converts an integer DNS record type to a [RecordType] enum value.
   static RecordType intToRecord(int type) {
     final records = _records.map((key, value) => MapEntry(value, key));
 
-    return records[type] ?? RecordType.A;
+    return records[type] ?? RecordType.unknown;
   }
 
   /// Converts a [RecordType] enum value to its corresponding integer DNS record
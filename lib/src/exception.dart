@@ -4,6 +4,135 @@
 /// resolution or parsing operations.
 abstract class DNSolveException implements Exception {
   const DNSolveException();
+
+  /// The category of the failure, telling transport problems apart from
+  /// malformed input or data.
+  ///
+  /// Defaults to [DNSolveErrorKind.other] for subclasses that don't override
+  /// it.
+  DNSolveErrorKind get kind => DNSolveErrorKind.other;
+}
+
+/// An enumeration that represents categories of lookup failures.
+///
+/// Transient kinds ([network] and [timeout]) are worth retrying, while the
+/// others will fail the same way again.
+enum DNSolveErrorKind {
+  /// The DNS provider could not be reached or answered with an HTTP server
+  /// error.
+  network,
+
+  /// The DNS provider did not answer in time.
+  timeout,
+
+  /// The data returned by the DNS provider could not be parsed.
+  parse,
+
+  /// The lookup was rejected because of invalid arguments, locally or by the
+  /// DNS provider with an HTTP client error or a `FORMERR` status.
+  config,
+
+  /// The queried domain name does not exist.
+  nxdomain,
+
+  /// The DNS server refused to answer the query.
+  refused,
+
+  /// The DNS server failed to answer the query, e.g. `SERVFAIL`.
+  server,
+
+  /// The lookup was aborted because its `DNSolve` instance was closed.
+  closed,
+
+  /// A failure that fits none of the other kinds, e.g. an exception defined
+  /// outside of this package.
+  other,
+}
+
+/// Represents an [Exception] that occured while sending a DNS request, before
+/// any response was received.
+class NetworkException extends DNSolveException {
+  const NetworkException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.network;
+
+  @override
+  String toString() => 'NetworkException: $message';
+}
+
+/// Represents an [Exception] that occured when a lookup was aborted, or
+/// could not be sent, because its `DNSolve` instance was closed.
+///
+/// Unlike a [NetworkException], this is never retried.
+class ClientClosedException extends DNSolveException {
+  const ClientClosedException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.closed;
+
+  @override
+  String toString() => 'ClientClosedException: $message';
+}
+
+/// Represents an [Exception] that occured when every attempt of a retried
+/// query failed, possibly across several providers.
+///
+/// It reports the outcome of each attempt, to tell which provider is at fault.
+/// The last failure is the one that stopped the retries, which is not
+/// necessarily transient, e.g. an HTTP client error, or a timeout once the
+/// deadline of the lookup passed.
+class AttemptsExhaustedException extends DNSolveException {
+  const AttemptsExhaustedException(this.failures);
+
+  /// The failure of each attempt, in order, along with the name of the
+  /// provider that was queried.
+  final List<(String provider, DNSolveException error)> failures;
+
+  @override
+  DNSolveErrorKind get kind => failures.last.$2.kind;
+
+  @override
+  String toString() {
+    final attempts = failures
+        .map((failure) => '${failure.$1} ${failure.$2.kind.name}')
+        .join(', ');
+
+    return 'AttemptsExhaustedException: all ${failures.length} attempts '
+        'failed: $attempts';
+  }
+}
+
+/// Represents an [Exception] that occured when a DNS provider did not answer
+/// a query within the configured query timeout.
+class QueryTimeoutException extends DNSolveException {
+  const QueryTimeoutException(this.timeout, {this.deadline});
+
+  /// The query timeout that was exceeded.
+  final Duration timeout;
+
+  /// The deadline the query was bound by, if it timed out at the deadline
+  /// rather than after the query timeout.
+  ///
+  /// A deadline that already passed fails with a [timeout] of
+  /// [Duration.zero], before any query is sent.
+  final DateTime? deadline;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.timeout;
+
+  @override
+  String toString() => switch (deadline) {
+        final deadline? => 'QueryTimeoutException: query timed out after '
+            '${timeout.inMilliseconds}ms, at the deadline '
+            '${deadline.toIso8601String()}',
+        null => 'QueryTimeoutException: query timed out after '
+            '${timeout.inMilliseconds}ms',
+      };
 }
 
 /// Represents an [Exception] that occured while processing an DNS request.
@@ -26,11 +155,57 @@ class ResponseException extends DNSolveException {
   /// The body of the response.
   final String body;
 
+  /// Server errors (5xx) are [DNSolveErrorKind.network] failures worth
+  /// retrying, while client errors (4xx) mean the query itself was rejected,
+  /// e.g. because of an invalid EDNS Client Subnet.
+  @override
+  DNSolveErrorKind get kind => statusCode >= 500
+      ? DNSolveErrorKind.network
+      : DNSolveErrorKind.config;
+
   @override
   String toString() =>
       '''Exception(Status Code: $statusCode, Response Headers: $headers, Response Body: $body)''';
 }
 
+/// Represents an [Exception] that occured when a DNS provider was reachable
+/// but answered a query with a failure status (DNS RCODE), e.g. `SERVFAIL`.
+class ResponseStatusException extends DNSolveException {
+  const ResponseStatusException(this.status, this.message);
+
+  /// The DNS response code the provider answered with.
+  final int? status;
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => switch (status) {
+        1 => DNSolveErrorKind.config,
+        3 => DNSolveErrorKind.nxdomain,
+        5 => DNSolveErrorKind.refused,
+        _ => DNSolveErrorKind.server,
+      };
+
+  @override
+  String toString() => 'ResponseStatusException: $message';
+}
+
+/// An exception indicating that the body of a DNS provider response is not
+/// valid JSON of the expected shape.
+///
+/// This is a specific type of [DNSolveException].
+class ResponseFormatException extends DNSolveException {
+  const ResponseFormatException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.parse;
+
+  @override
+  String toString() => 'ResponseFormatException: $message';
+}
+
 /// An exception indicating that an error occurred while parsing or processing a
 /// Service (SRV) record.
 ///
@@ -39,4 +214,55 @@ class SRVRecordFormatException extends DNSolveException {
   const SRVRecordFormatException(this.message);
 
   final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.parse;
+}
+
+/// An exception indicating that a Uniform Resource Identifier (URI) record
+/// could not be parsed.
+///
+/// This is a specific type of [DNSolveException].
+class URIRecordFormatException extends DNSolveException {
+  const URIRecordFormatException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.parse;
+
+  @override
+  String toString() => 'URIRecordFormatException: $message';
+}
+
+/// An exception indicating that a domain name is malformed and was rejected
+/// before being queried, e.g. because it is too long.
+///
+/// This is a specific type of [DNSolveException].
+class DomainFormatException extends DNSolveException {
+  const DomainFormatException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.config;
+
+  @override
+  String toString() => 'DomainFormatException: $message';
+}
+
+/// An exception indicating that an IP address or CIDR range is malformed or
+/// too large to be processed.
+///
+/// This is a specific type of [DNSolveException].
+class AddressFormatException extends DNSolveException {
+  const AddressFormatException(this.message);
+
+  final String message;
+
+  @override
+  DNSolveErrorKind get kind => DNSolveErrorKind.config;
+
+  @override
+  String toString() => 'AddressFormatException: $message';
 }
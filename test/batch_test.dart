@@ -0,0 +1,94 @@
+import 'dart:async';
+
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('lookupBatchStream', () {
+    test('reports failures with their index', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async =>
+              request.url.queryParameters['name'] == 'bad.example.com.'
+                  ? http.Response('unavailable', 500)
+                  : echoResponse(request),
+        ),
+      );
+
+      final events = await dnsolve
+          .lookupBatchStream(['good.example.com', 'bad.example.com'])
+          .toList();
+      events.sort((a, b) => a.index.compareTo(b.index));
+
+      expect(events.map((event) => event.index), [0, 1]);
+      expect(events[0].response, isNotNull);
+      expect(events[0].error, isNull);
+      expect(events[1].response, isNull);
+      expect(events[1].error, isA<ResponseException>());
+    });
+
+    test('stops sending lookups once cancelled', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests++;
+          await Future<void>.delayed(const Duration(milliseconds: 10));
+          return echoResponse(request);
+        }),
+      );
+
+      final stream = dnsolve.lookupBatchStream(
+        [for (var i = 0; i < 20; i++) 'host$i.example.com'],
+        concurrency: 1,
+      );
+      final received = Completer<void>();
+      final subscription = stream.listen((event) => received.complete());
+
+      await received.future;
+      await subscription.cancel();
+      await Future<void>.delayed(const Duration(milliseconds: 100));
+
+      // The lookup in flight when cancelling may still be sent.
+      expect(requests, inInclusiveRange(1, 2));
+    });
+  });
+
+  group('maxBatchSize', () {
+    var requests = 0;
+    final dnsolve = DNSolve(
+      maxBatchSize: 2,
+      client: MockClient((request) async {
+        requests++;
+        return echoResponse(request, status: 3);
+      }),
+    );
+
+    setUp(() => requests = 0);
+
+    test('rejects larger batches before sending any lookup', () async {
+      const domains = ['a.example', 'b.example', 'c.example'];
+      expect(() => dnsolve.lookupBatchStream(domains), throwsRangeError);
+      expect(
+        () => dnsolve.reverseLookupBatch(['192.0.2.1', '192.0.2.2', '::1']),
+        throwsRangeError,
+      );
+      await expectLater(
+        dnsolve.reverseLookupCidr('192.0.2.0/29'),
+        throwsRangeError,
+      );
+      expect(requests, 0);
+    });
+
+    test('accepts batches of that size', () async {
+      final events =
+          await dnsolve.lookupBatchStream(['a.example', 'b.example']).toList();
+
+      expect(events, hasLength(2));
+      expect(requests, 2);
+    });
+  });
+}
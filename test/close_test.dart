@@ -0,0 +1,72 @@
+import 'dart:async';
+
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('close()', () {
+    test('aborts in-flight queries without retrying them', () async {
+      var requests = 0;
+      final sent = Completer<void>();
+      final aborted = Completer<void>();
+      final dnsolve = DNSolve(
+        attempts: 3,
+        client: MockClient((request) async {
+          requests++;
+          sent.complete();
+          await aborted.future;
+          throw http.ClientException('Connection closed');
+        }),
+      );
+
+      final lookup = dnsolve.lookup('example.com');
+      await sent.future;
+      dnsolve.close();
+      aborted.complete();
+
+      await expectLater(lookup, throwsA(isA<ClientClosedException>()));
+      expect(requests, 1);
+    });
+
+    test('does not replace a supplied client', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests++;
+          return echoResponse(request);
+        }),
+      )..close();
+
+      await expectLater(
+        dnsolve.lookup('example.com'),
+        throwsA(isA<ClientClosedException>()),
+      );
+      expect(requests, 0);
+    });
+
+    test('stops batches without starting their remaining lookups', () async {
+      var requests = 0;
+      late final DNSolve dnsolve;
+      dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests++;
+          dnsolve.close();
+          throw http.ClientException('Connection closed');
+        }),
+      );
+
+      await expectLater(
+        dnsolve.reverseLookupBatch(
+          ['192.0.2.1', '192.0.2.2', '192.0.2.3'],
+          concurrency: 1,
+        ),
+        throwsA(isA<ClientClosedException>()),
+      );
+      expect(requests, 1);
+    });
+  });
+}
@@ -0,0 +1,136 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+/// Returns an instance answering every query with the given records for
+/// `a.example.`, along with the provider [comment] if any.
+DNSolve _answering(List<Map<String, Object?>> answer, {String? comment}) =>
+    DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse({
+          ...answerBody(
+            name: 'a.example.',
+            type: int.parse(request.url.queryParameters['type']!),
+            answer: answer,
+          ),
+          if (comment != null) 'Comment': comment,
+        }),
+      ),
+    );
+
+void main() {
+  group('CNAME chains', () {
+    final chain = [
+      answerRecord('a.example.', 5, 'b.example.'),
+      answerRecord('b.example.', 5, 'c.example.'),
+      answerRecord('c.example.', 5, 'd.example.'),
+      answerRecord('d.example.', 1, '192.0.2.1'),
+    ];
+
+    test('are followed to the canonical name', () async {
+      final response = await _answering(chain).lookup('a.example');
+
+      expect(response.status, 0);
+      expect(response.canonicalName, 'd.example.');
+    });
+
+    test('longer than maxCnameHops fail with SERVFAIL', () async {
+      final response =
+          await _answering(chain).lookup('a.example', maxCnameHops: 2);
+
+      expect(response.status, 2);
+      expect(response.comment, contains('exceeded 2 hops'));
+    });
+
+    test('as long as maxCnameHops are followed', () async {
+      final response =
+          await _answering(chain).lookup('a.example', maxCnameHops: 3);
+
+      expect(response.status, 0);
+      expect(response.canonicalName, 'd.example.');
+    });
+
+    test('reject a negative maxCnameHops', () async {
+      await expectLater(
+        _answering(chain).lookup('a.example', maxCnameHops: -1),
+        throwsRangeError,
+      );
+    });
+
+    test('that loop fail with SERVFAIL', () async {
+      final response = await _answering(
+        [
+          answerRecord('a.example.', 5, 'b.example.'),
+          answerRecord('b.example.', 5, 'a.example.'),
+        ],
+        comment: 'Response from 192.0.2.53.',
+      ).lookup('a.example');
+
+      expect(response.status, 2);
+      expect(response.canonicalName, isNull);
+      expect(
+        response.comment,
+        'Response from 192.0.2.53.; '
+        'CNAME loop detected while resolving a.example.',
+      );
+    });
+
+    test('without records of the queried type are alias-only', () async {
+      final response = await _answering([
+        answerRecord('a.example.', 5, 'b.example.'),
+      ]).lookup('a.example');
+
+      expect(response.status, 0);
+      expect(response.isAliasOnly, isTrue);
+      expect(
+        response.comment,
+        'a.example. is an alias of b.example., which has no A records',
+      );
+    });
+
+    test('are not alias-only when CNAME records were queried', () async {
+      final response = await _answering([
+        answerRecord('a.example.', 5, 'b.example.'),
+      ]).lookup('a.example', type: RecordType.cname);
+
+      expect(response.isAliasOnly, isFalse);
+    });
+
+    test('follow the last of duplicate CNAME records', () async {
+      final response = await _answering([
+        answerRecord('a.example.', 5, 'b.example.'),
+        answerRecord('a.example.', 5, 'c.example.'),
+        answerRecord('c.example.', 1, '192.0.2.1'),
+      ]).lookup('a.example');
+
+      expect(response.canonicalName, 'c.example.');
+    });
+  });
+
+  group('filterName', () {
+    final answer = [
+      answerRecord('A.Example', 5, 'b.example.'),
+      answerRecord('b.example.', 1, '192.0.2.1'),
+      answerRecord('a.example.', 1, '192.0.2.2'),
+    ];
+
+    test('keeps only the records owned by the queried name', () async {
+      final response =
+          await _answering(answer).lookup('a.example', filterName: true);
+
+      expect(
+        response.answer!.records!.map((record) => record.data),
+        ['b.example.', '192.0.2.2'],
+      );
+      expect(response.canonicalName, 'b.example.');
+    });
+
+    test('keeps every record by default', () async {
+      final response = await _answering(answer).lookup('a.example');
+
+      expect(response.answer!.records, hasLength(3));
+    });
+  });
+}
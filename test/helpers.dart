@@ -0,0 +1,41 @@
+import 'dart:convert';
+
+import 'package:http/http.dart' as http;
+
+/// Builds the JSON body of a provider answer.
+Map<String, Object?> answerBody({
+  String name = 'example.com.',
+  int type = 1,
+  int? status = 0,
+  List<Map<String, Object?>> answer = const [],
+}) =>
+    {
+      if (status != null) 'Status': status,
+      'TC': false,
+      'RD': true,
+      'RA': true,
+      'AD': false,
+      'CD': false,
+      'Question': [
+        {'name': name, 'type': type},
+      ],
+      'Answer': answer,
+    };
+
+/// Builds a record of a provider answer.
+Map<String, Object?> answerRecord(String name, int type, String data) =>
+    {'name': name, 'type': type, 'TTL': 300, 'data': data};
+
+/// Builds a provider response with the given JSON [body].
+http.Response jsonResponse(Object? body, [int statusCode = 200]) =>
+    http.Response(json.encode(body), statusCode);
+
+/// Answers every query with an empty answer for the queried name and type.
+http.Response echoResponse(http.Request request, {int status = 0}) =>
+    jsonResponse(
+      answerBody(
+        name: request.url.queryParameters['name']!,
+        type: int.parse(request.url.queryParameters['type']!),
+        status: status,
+      ),
+    );
@@ -0,0 +1,252 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+/// Returns an instance answering A and AAAA queries with one address each,
+/// unless answered otherwise by [respond], and recording the queried types.
+DNSolve _addresses(
+  List<String> types, {
+  Future<http.Response?> Function(String type)? respond,
+}) =>
+    DNSolve(
+      client: MockClient((request) async {
+        final type = request.url.queryParameters['type']!;
+        types.add(type);
+        final response = await respond?.call(type);
+        if (response != null) {
+          return response;
+        }
+
+        return jsonResponse(
+          type == '1'
+              ? answerBody(
+                  answer: [answerRecord('example.com.', 1, '192.0.2.1')],
+                )
+              : answerBody(
+                  type: 28,
+                  answer: [answerRecord('example.com.', 28, '2001:db8::1')],
+                ),
+        );
+      }),
+    );
+
+void main() {
+  group('lookupMulti', () {
+    test('merges the answers of every type', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          final type = int.parse(request.url.queryParameters['type']!);
+          return jsonResponse(
+            answerBody(
+              type: type,
+              answer: [
+                if (type == 1) answerRecord('example.com.', 1, '192.0.2.1'),
+                if (type == 28) answerRecord('example.com.', 28, '2001:db8::1'),
+              ],
+            ),
+          );
+        }),
+      );
+
+      final response = await dnsolve.lookupMulti('example.com');
+
+      expect(response.status, 0);
+      expect(response.addresses, ['192.0.2.1', '2001:db8::1']);
+      expect(response.questions, hasLength(2));
+    });
+
+    test('flags responses of which only some lookups succeeded', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => request.url.queryParameters['type'] == '1'
+              ? echoResponse(request)
+              : http.Response('unavailable', 503),
+        ),
+      );
+
+      final partial = await dnsolve.lookupMulti('example.com');
+      final complete = await dnsolve.lookupMulti(
+        'example.com',
+        types: [RecordType.A],
+      );
+
+      expect(partial.status, 0);
+      expect(partial.partial, isTrue);
+      expect(partial.comment, startsWith('Partial failure: AAAA ('));
+      expect(complete.partial, isFalse);
+    });
+
+    test('rejects an empty list of types', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => echoResponse(request)),
+      );
+
+      await expectLater(
+        dnsolve.lookupMulti('example.com', types: []),
+        throwsArgumentError,
+      );
+    });
+
+    test('throws the failure of the first type when all fail', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          // The A lookup fails last, after the AAAA one.
+          if (request.url.queryParameters['type'] == '1') {
+            await Future<void>.delayed(const Duration(milliseconds: 20));
+            return http.Response('unavailable', 503);
+          }
+
+          return http.Response('bad request', 400);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.lookupMulti('example.com'),
+        throwsA(
+          isA<ResponseException>()
+              .having((error) => error.statusCode, 'statusCode', 503),
+        ),
+      );
+    });
+  });
+
+  group('lookupIps', () {
+    test('resolves only the requested address family', () async {
+      final types = <String>[];
+      final dnsolve = _addresses(types);
+
+      final ipv4 =
+          await dnsolve.lookupIps('example.com', strategy: IPStrategy.ipv4Only);
+      final ipv6 =
+          await dnsolve.lookupIps('example.com', strategy: IPStrategy.ipv6Only);
+
+      expect(ipv4.addresses, ['192.0.2.1']);
+      expect(ipv6.addresses, ['2001:db8::1']);
+      expect(types, ['1', '28']);
+    });
+
+    test('resolves both address families by default', () async {
+      final types = <String>[];
+
+      final response = await _addresses(types).lookupIps('example.com');
+
+      expect(response.addresses, ['192.0.2.1', '2001:db8::1']);
+      expect(types, unorderedEquals(['1', '28']));
+    });
+
+    test('does not fall back when the preferred family answers', () async {
+      final types = <String>[];
+
+      final response = await _addresses(types)
+          .lookupIps('example.com', strategy: IPStrategy.ipv6ThenIpv4);
+
+      expect(response.addresses, ['2001:db8::1']);
+      expect(types, ['28']);
+    });
+
+    test('falls back when the preferred family has no addresses', () async {
+      final types = <String>[];
+      final dnsolve = _addresses(
+        types,
+        respond: (type) async =>
+            type == '1' ? jsonResponse(answerBody()) : null,
+      );
+
+      final response = await dnsolve.lookupIps(
+        'example.com',
+        strategy: IPStrategy.ipv4ThenIpv6,
+      );
+
+      expect(response.addresses, ['2001:db8::1']);
+      expect(types, ['1', '28']);
+    });
+
+    test('falls back when the preferred family fails', () async {
+      final types = <String>[];
+      final dnsolve = _addresses(
+        types,
+        respond: (type) async => switch (type) {
+          '28' => jsonResponse(answerBody(type: 28, status: 2)),
+          _ => null,
+        },
+      );
+
+      final response = await dnsolve.lookupIps(
+        'example.com',
+        strategy: IPStrategy.ipv6ThenIpv4,
+      );
+
+      expect(response.status, 0);
+      expect(response.addresses, ['192.0.2.1']);
+      expect(types, ['28', '1']);
+    });
+
+    test('falls back when the preferred family lookup throws', () async {
+      final types = <String>[];
+      final dnsolve = _addresses(
+        types,
+        respond: (type) async =>
+            type == '1' ? http.Response('unavailable', 503) : null,
+      );
+
+      final response = await dnsolve.lookupIps(
+        'example.com',
+        strategy: IPStrategy.ipv4ThenIpv6,
+      );
+
+      expect(response.addresses, ['2001:db8::1']);
+      expect(types, ['1', '28']);
+    });
+
+    test('reports the preferred family when neither has addresses', () async {
+      final dnsolve = _addresses(
+        [],
+        respond: (type) async => type == '1'
+            ? http.Response('unavailable', 503)
+            : http.Response('bad request', 400),
+      );
+
+      await expectLater(
+        dnsolve.lookupIps('example.com', strategy: IPStrategy.ipv4ThenIpv6),
+        throwsA(
+          isA<ResponseException>()
+              .having((error) => error.statusCode, 'statusCode', 503),
+        ),
+      );
+    });
+  });
+
+  group('lookupHappyEyeballs', () {
+    test('interleaves address families, IPv6 first', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            request.url.queryParameters['type'] == '1'
+                ? answerBody(
+                    answer: [
+                      answerRecord('example.com.', 1, '192.0.2.1'),
+                      answerRecord('example.com.', 1, '192.0.2.2'),
+                      answerRecord('example.com.', 1, '192.0.2.3'),
+                    ],
+                  )
+                : answerBody(
+                    type: 28,
+                    answer: [answerRecord('example.com.', 28, '2001:db8::1')],
+                  ),
+          ),
+        ),
+      );
+
+      final records = await dnsolve.lookupHappyEyeballs('example.com');
+
+      expect(
+        records.map((record) => record.data),
+        ['2001:db8::1', '192.0.2.1', '192.0.2.2', '192.0.2.3'],
+      );
+      expect(records.first.rType, RecordType.aaaa);
+    });
+  });
+}
@@ -0,0 +1,750 @@
+import 'dart:convert';
+
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('lookup', () {
+    test('sends the query to the provider', () async {
+      final requests = <Uri>[];
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests.add(request.url);
+          return echoResponse(request);
+        }),
+      );
+
+      await dnsolve.lookup(
+        'example.com',
+        type: RecordType.mx,
+        dnsSec: true,
+        provider: DNSProvider.cloudflare,
+      );
+
+      expect(requests, hasLength(1));
+      expect(requests.single.host, 'cloudflare-dns.com');
+      expect(requests.single.queryParameters, {
+        'name': 'example.com.',
+        'type': '15',
+        'do': 'true',
+      });
+    });
+
+    for (final (dnsSec, validate, params) in [
+      (false, true, {'do': 'false'}),
+      (true, true, {'do': 'true'}),
+      (false, false, {'do': 'false', 'cd': 'true'}),
+      (true, false, {'do': 'true', 'cd': 'true'}),
+    ]) {
+      test('sends $params for dnsSec $dnsSec, validate $validate', () async {
+        final requests = <Uri>[];
+        final dnsolve = DNSolve(
+          client: MockClient((request) async {
+            requests.add(request.url);
+            return echoResponse(request);
+          }),
+        );
+
+        final response = await dnsolve.lookup(
+          'example.com',
+          dnsSec: dnsSec,
+          validate: validate,
+        );
+
+        expect(requests.single.queryParameters, {
+          'name': 'example.com.',
+          'type': '1',
+          ...params,
+        });
+        expect(response.query!.dnsSec, dnsSec);
+        expect(response.query!.validate, validate);
+      });
+    }
+
+    test('sends the EDNS Client Subnet and reads its scope', () async {
+      final requests = <Uri>[];
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests.add(request.url);
+          return jsonResponse({
+            ...answerBody(),
+            'edns_client_subnet': '203.0.113.0/0',
+          });
+        }),
+      );
+
+      final response = await dnsolve.lookup(
+        'example.com',
+        clientSubnet: '203.0.113.0/24',
+      );
+
+      expect(
+        requests.single.queryParameters['edns_client_subnet'],
+        '203.0.113.0/24',
+      );
+      expect(response.query!.clientSubnet, '203.0.113.0/24');
+      expect(response.clientSubnet, '203.0.113.0/0');
+      expect(response.clientSubnetScope, 0);
+    });
+
+    test('drops duplicate records with dedupe', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(
+              answer: [
+                answerRecord('example.com.', 1, '192.0.2.1'),
+                answerRecord('EXAMPLE.com.', 1, '192.0.2.1'),
+                answerRecord('example.com.', 1, '192.0.2.2'),
+              ],
+            ),
+          ),
+        ),
+      );
+
+      final full = await dnsolve.lookup('example.com');
+      final deduped = await dnsolve.lookup('example.com', dedupe: true);
+
+      expect(full.addresses, ['192.0.2.1', '192.0.2.1', '192.0.2.2']);
+      expect(deduped.addresses, ['192.0.2.1', '192.0.2.2']);
+    });
+
+    test('keeps SRV records of different owners apart with dedupe', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(
+              type: 33,
+              answer: [
+                answerRecord('_a._tcp.example.com.', 33, '10 5 5060 sip.'),
+                answerRecord('_b._tcp.example.com.', 33, '10 5 5060 sip.'),
+                answerRecord('_b._tcp.example.com.', 33, '10 5 5060 sip.'),
+              ],
+            ),
+          ),
+        ),
+      );
+
+      final response = await dnsolve.lookup(
+        'example.com',
+        type: RecordType.srv,
+        dedupe: true,
+      );
+
+      expect(response.answer!.records, hasLength(2));
+      expect(
+        response.answer!.srvs!.map((srv) => srv.fqdn),
+        ['_a._tcp.example.com.', '_b._tcp.example.com.'],
+      );
+    });
+  });
+
+  test('rotates address records on each lookup with rotate', () async {
+    final dnsolve = DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse(
+          answerBody(
+            answer: [
+              answerRecord('example.com.', 5, 'pool.example.com.'),
+              answerRecord('pool.example.com.', 1, '192.0.2.1'),
+              answerRecord('pool.example.com.', 1, '192.0.2.2'),
+              answerRecord('pool.example.com.', 1, '192.0.2.3'),
+            ],
+          ),
+        ),
+      ),
+    );
+
+    final first = await dnsolve.lookup('example.com', rotate: true);
+    final unrotated = await dnsolve.lookup('example.com');
+    final second = await dnsolve.lookup('example.com', rotate: true);
+
+    expect(first.addresses, ['192.0.2.1', '192.0.2.2', '192.0.2.3']);
+    expect(unrotated.addresses, ['192.0.2.1', '192.0.2.2', '192.0.2.3']);
+    expect(second.addresses, ['192.0.2.2', '192.0.2.3', '192.0.2.1']);
+    expect(second.answer!.records!.first.rType, RecordType.cname);
+  });
+
+  test('lists the records of all sections with their section', () async {
+    final dnsolve = DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse({
+          ...answerBody(answer: [answerRecord('example.com.', 1, '192.0.2.1')]),
+          'Authority': [
+            answerRecord('example.com.', 2, 'ns.example.com.'),
+          ],
+          'Additional': [
+            answerRecord('ns.example.com.', 1, '192.0.2.53'),
+          ],
+        }),
+      ),
+    );
+
+    final response = await dnsolve.lookup('example.com');
+
+    expect(
+      response.records.map((record) => (record.data, record.section)),
+      [
+        ('192.0.2.1', DNSSection.answer),
+        ('ns.example.com.', DNSSection.authority),
+        ('192.0.2.53', DNSSection.additional),
+      ],
+    );
+  });
+
+  test('groups answer records by type name', () async {
+    final dnsolve = DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse(
+          answerBody(
+            type: 255,
+            answer: [
+              answerRecord('example.com.', 1, '192.0.2.1'),
+              answerRecord('example.com.', 16, '"v=spf1 -all"'),
+              answerRecord('example.com.', 1, '192.0.2.2'),
+              answerRecord('example.com.', 3, r'\# 0'),
+            ],
+          ),
+        ),
+      ),
+    );
+
+    final response = await dnsolve.lookup('example.com', type: RecordType.any);
+    final groups = response.answer!.groupedByType;
+
+    expect(groups.keys, ['A', 'TXT', 'TYPE3']);
+    expect(
+      groups['A']!.map((record) => record.data),
+      ['192.0.2.1', '192.0.2.2'],
+    );
+    expect(groups['TYPE3']!.single.typeId, 3);
+  });
+
+  group('negative answers', () {
+    // Answers with the given status and no records, along with the SOA of the
+    // zone in the authority section.
+    DNSolve negative(int status) => DNSolve(
+          client: MockClient(
+            (request) async => jsonResponse({
+              ...answerBody(status: status),
+              'Authority': [
+                answerRecord(
+                  'example.com.',
+                  6,
+                  'ns.example.com. admin.example.com. 1 7200 900 1209600 60',
+                ),
+              ],
+            }),
+          ),
+        );
+
+    test('without records of the queried type are NODATA', () async {
+      final response = await negative(0).lookup('example.com');
+
+      expect(response.isNoData, isTrue);
+      expect(response.soa!.name, 'example.com.');
+      expect(response.soa!.data, startsWith('ns.example.com. '));
+    });
+
+    test('for names that do not exist are not NODATA', () async {
+      final response = await negative(3).lookup('example.com');
+
+      expect(response.status, 3);
+      expect(response.isNoData, isFalse);
+      expect(response.soa, isNotNull);
+    });
+
+    test('are not NODATA with records of the queried type', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(answer: [answerRecord('example.com.', 1, '192.0.2.1')]),
+          ),
+        ),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+
+      expect(response.isNoData, isFalse);
+      expect(response.soa, isNull);
+    });
+  });
+
+  group('answer limits', () {
+    final dnsolve = DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse(
+          answerBody(
+            answer: [
+              for (var i = 1; i <= 5; i++)
+                answerRecord('example.com.', 1, '192.0.2.$i'),
+            ],
+          ),
+        ),
+      ),
+    );
+
+    test('cap the answer and report its original size', () async {
+      final response = await dnsolve.lookup('example.com', maxAnswers: 2);
+
+      expect(response.addresses, ['192.0.2.1', '192.0.2.2']);
+      expect(response.truncatedAnswers, isTrue);
+      expect(response.totalAnswers, 5);
+    });
+
+    test('leave smaller answers alone', () async {
+      final response = await dnsolve.lookup('example.com', maxAnswers: 5);
+
+      expect(response.addresses, hasLength(5));
+      expect(response.truncatedAnswers, isFalse);
+      expect(response.totalAnswers, isNull);
+    });
+
+    test('accept a limit of zero', () async {
+      final response = await dnsolve.lookup('example.com', maxAnswers: 0);
+
+      expect(response.addresses, isEmpty);
+      expect(response.totalAnswers, 5);
+    });
+
+    test('reject negative limits', () async {
+      await expectLater(
+        dnsolve.lookup('example.com', maxAnswers: -1),
+        throwsRangeError,
+      );
+    });
+  });
+
+  group('trailing dots', () {
+    test('are added to queried, question and record names', () async {
+      final names = <String>[];
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          names.add(request.url.queryParameters['name']!);
+          return jsonResponse(
+            answerBody(
+              name: 'example.com',
+              answer: [answerRecord('example.com', 1, '192.0.2.1')],
+            ),
+          );
+        }),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+      await dnsolve.lookup('example.com.');
+
+      expect(names, ['example.com.', 'example.com.']);
+      expect(response.questions!.single.name, 'example.com.');
+      expect(response.answer!.records!.single.name, 'example.com.');
+    });
+  });
+
+  group('TTLs', () {
+    test('are kept as an absolute expiry time', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(answer: [answerRecord('example.com.', 1, '192.0.2.1')]),
+          ),
+        ),
+      );
+
+      final before = DateTime.now();
+      final response = await dnsolve.lookup('example.com');
+      final after = DateTime.now();
+
+      final expires = response.answer!.records!.single.expires!;
+      const ttl = Duration(seconds: 300);
+      expect(expires.isBefore(before.add(ttl)), isFalse);
+      expect(expires.isAfter(after.add(ttl)), isFalse);
+    });
+
+    test('are summarized by the shortest one', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            request.url.queryParameters['type'] == '1'
+                ? answerBody(
+                    answer: [
+                      answerRecord('example.com.', 1, '192.0.2.1'),
+                      {
+                        ...answerRecord('example.com.', 1, '192.0.2.2'),
+                        'TTL': 60,
+                      },
+                    ],
+                  )
+                : answerBody(type: 28),
+          ),
+        ),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+      final empty = await dnsolve.lookup('example.com', type: RecordType.aaaa);
+
+      expect(response.minTtl, 60);
+      expect(empty.minTtl, isNull);
+    });
+  });
+
+  group('dig output', () {
+    test('renders the header, question and answer', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(answer: [answerRecord('example.com.', 1, '192.0.2.1')]),
+          ),
+        ),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+
+      expect(
+        response.toDig,
+        ';; ->>HEADER<<- status: NOERROR\n'
+        ';; flags: rd ra; QUERY: 1, ANSWER: 1, AUTHORITY: 0, ADDITIONAL: 0\n'
+        '\n'
+        ';; QUESTION SECTION:\n'
+        ';example.com.\t\tIN\tA\n'
+        '\n'
+        ';; ANSWER SECTION:\n'
+        'example.com.\t300\tIN\tA\t192.0.2.1\n',
+      );
+    });
+
+    test('names failure statuses', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => echoResponse(request, status: 3)),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+
+      expect(response.toDig, startsWith(';; ->>HEADER<<- status: NXDOMAIN\n'));
+      expect(response.toDig, isNot(contains('ANSWER SECTION')));
+    });
+  });
+
+  group('DNSSEC status', () {
+    // Looks up a name answered with the given status, flags and comment.
+    Future<DNSSECStatus> statusOf({
+      int status = 0,
+      bool ad = false,
+      bool cd = false,
+      String? comment,
+      bool dnsSec = false,
+    }) async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse({
+            ...answerBody(status: status),
+            'AD': ad,
+            'CD': cd,
+            if (comment != null) 'Comment': comment,
+          }),
+        ),
+      );
+
+      return (await dnsolve.lookup('example.com', dnsSec: dnsSec))
+          .dnssecStatus;
+    }
+
+    test('is secure for authenticated answers', () async {
+      expect(await statusOf(ad: true), DNSSECStatus.secure);
+    });
+
+    test('is unchecked when checking was disabled', () async {
+      expect(await statusOf(ad: true, cd: true), DNSSECStatus.unchecked);
+    });
+
+    test('is insecure for unauthenticated answers', () async {
+      expect(await statusOf(), DNSSECStatus.insecure);
+      expect(await statusOf(dnsSec: true), DNSSECStatus.insecure);
+    });
+
+    test('is bogus for validation failures', () async {
+      expect(
+        await statusOf(status: 2, comment: 'EDE(6): DNSSEC Bogus'),
+        DNSSECStatus.bogus,
+      );
+    });
+
+    test('is indeterminate when validation could not conclude', () async {
+      expect(
+        await statusOf(status: 2, comment: 'EDE(5): DNSSEC Indeterminate'),
+        DNSSECStatus.indeterminate,
+      );
+    });
+
+    test('is indeterminate for unexplained failures with DNSSEC', () async {
+      expect(
+        await statusOf(status: 2, dnsSec: true),
+        DNSSECStatus.indeterminate,
+      );
+      expect(await statusOf(status: 2), DNSSECStatus.insecure);
+      expect(
+        await statusOf(status: 2, dnsSec: true, comment: 'EDE(22): '),
+        DNSSECStatus.insecure,
+      );
+    });
+  });
+
+  group('ANY queries', () {
+    test('explain minimal answers', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(
+              type: 255,
+              answer: [answerRecord('example.com.', 13, '"RFC8482" ""')],
+            ),
+          ),
+        ),
+      );
+
+      final response =
+          await dnsolve.lookup('example.com', type: RecordType.any);
+
+      expect(response.status, 0);
+      expect(response.comment, contains('RFC 8482'));
+    });
+
+    test('explain refused answers after the provider comment', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse({
+            ...answerBody(type: 255, status: 5),
+            'Comment': 'Refused by policy.',
+          }),
+        ),
+      );
+
+      final response =
+          await dnsolve.lookup('example.com', type: RecordType.any);
+
+      expect(response.comment, startsWith('Refused by policy.; ANY queries'));
+    });
+
+    test('leave full answers alone', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(
+              type: 255,
+              answer: [
+                answerRecord('example.com.', 1, '192.0.2.1'),
+                answerRecord('example.com.', 16, '"v=spf1 -all"'),
+              ],
+            ),
+          ),
+        ),
+      );
+
+      final response =
+          await dnsolve.lookup('example.com', type: RecordType.any);
+
+      expect(response.comment, isNull);
+    });
+  });
+
+  group('domain validation', () {
+    final names = <String>[];
+    final dnsolve = DNSolve(
+      client: MockClient((request) async {
+        names.add(request.url.queryParameters['name']!);
+        return echoResponse(request);
+      }),
+    );
+
+    setUp(names.clear);
+
+    for (final domain in ['.example.com', 'a..example.com']) {
+      test('rejects "$domain"', () async {
+        await expectLater(
+          dnsolve.lookup(domain),
+          throwsA(isA<DomainFormatException>()),
+        );
+        expect(names, isEmpty);
+      });
+    }
+
+    test('rejects labels longer than 63 octets', () async {
+      await expectLater(
+        dnsolve.lookup('${'a' * 64}.example.com'),
+        throwsA(isA<DomainFormatException>()),
+      );
+    });
+
+    test('rejects names longer than 253 octets', () async {
+      await expectLater(
+        dnsolve.lookup(List.filled(64, 'abc').join('.')),
+        throwsA(isA<DomainFormatException>()),
+      );
+    });
+
+    for (final (description, domain) in [
+      ('an empty name', ''),
+      ('a blank name', '   '),
+      ('a whitespace-only name', '\t\n'),
+    ]) {
+      test('rejects $description', () async {
+        await expectLater(
+          dnsolve.lookup(domain),
+          throwsA(isA<DomainFormatException>()),
+        );
+        expect(names, isEmpty);
+      });
+    }
+
+    test('trims surrounding whitespace', () async {
+      await dnsolve.lookup('  example.com \n');
+
+      expect(names, ['example.com.']);
+    });
+
+    test('accepts names of exactly 253 octets', () async {
+      final domain = '${List.filled(50, 'abcd').join('.')}.abc';
+      await dnsolve.lookup(domain);
+
+      expect(names, ['$domain.']);
+    });
+  });
+
+  test('reports malformed responses as ResponseFormatException', () async {
+    for (final body in [
+      'not json',
+      '[]',
+      json.encode({'Status': 'NOERROR'}),
+      json.encode({
+        'Status': 0,
+        'Answer': [
+          {'name': 'example.com.', 'type': 1, 'data': '192.0.2.1'},
+        ],
+      }),
+    ]) {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => http.Response(body, 200)),
+      );
+
+      await expectLater(
+        dnsolve.lookup('example.com'),
+        throwsA(isA<ResponseFormatException>()),
+        reason: body,
+      );
+    }
+  });
+
+  group('record types by id', () {
+    final dnsolve = DNSolve(
+      client: MockClient((request) async => echoResponse(request)),
+    );
+
+    test('are required for unknown types', () async {
+      await expectLater(
+        dnsolve.lookup('example.com', type: RecordType.unknown),
+        throwsArgumentError,
+      );
+
+      final response = await dnsolve.lookup('example.com', typeId: 3);
+      expect(response.query!.typeId, 3);
+      expect(response.questions!.single.rType, RecordType.unknown);
+      expect(response.toDig, contains('IN\tTYPE3'));
+    });
+
+    test('must be between 0 and 65535', () async {
+      for (final typeId in [-1, 65536]) {
+        await expectLater(
+          dnsolve.lookup('example.com', typeId: typeId),
+          throwsRangeError,
+        );
+      }
+    });
+
+    test('explain refused ANY answers', () async {
+      final refusing = DNSolve(
+        client: MockClient((request) async => echoResponse(request, status: 5)),
+      );
+
+      final response = await refusing.lookup('example.com', typeId: 255);
+
+      expect(response.comment, contains('RFC 8482'));
+    });
+  });
+
+  group('search domains', () {
+    final names = <String>[];
+    final dnsolve = DNSolve(
+      searchDomains: ['corp.example.com', 'example.org.'],
+      client: MockClient((request) async {
+        names.add(request.url.queryParameters['name']!);
+        return echoResponse(request, status: 3);
+      }),
+    );
+
+    setUp(names.clear);
+
+    test('are tried first for names with fewer than ndots dots', () async {
+      await dnsolve.lookup('intranet');
+
+      expect(names, [
+        'intranet.corp.example.com.',
+        'intranet.example.org.',
+        'intranet.',
+      ]);
+    });
+
+    test('are tried last for names with at least ndots dots', () async {
+      await dnsolve.lookup('www.example');
+
+      expect(names, [
+        'www.example.',
+        'www.example.corp.example.com.',
+        'www.example.example.org.',
+      ]);
+    });
+
+    test('are not applied to fully qualified names', () async {
+      await dnsolve.lookup('intranet.');
+
+      expect(names, ['intranet.']);
+    });
+
+    test('stop at the first name that exists', () async {
+      final tried = <String>[];
+      final resolving = DNSolve(
+        searchDomains: ['corp.example.com', 'example.org'],
+        client: MockClient((request) async {
+          tried.add(request.url.queryParameters['name']!);
+          return echoResponse(request, status: tried.length == 1 ? 3 : 0);
+        }),
+      );
+
+      final response = await resolving.lookup('intranet');
+
+      expect(tried, ['intranet.corp.example.com.', 'intranet.example.org.']);
+      expect(response.query!.name, 'intranet.example.org.');
+    });
+
+    test('follow the configured ndots', () async {
+      final tried = <String>[];
+      final strict = DNSolve(
+        searchDomains: ['corp.example.com'],
+        ndots: 2,
+        client: MockClient((request) async {
+          tried.add(request.url.queryParameters['name']!);
+          return echoResponse(request, status: 3);
+        }),
+      );
+
+      await strict.lookup('www.example');
+
+      expect(tried, ['www.example.corp.example.com.', 'www.example.']);
+    });
+
+    test('reject a negative ndots', () {
+      expect(() => DNSolve(ndots: -1), throwsRangeError);
+    });
+  });
+}
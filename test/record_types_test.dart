@@ -0,0 +1,219 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+/// Looks up [type] with the provider answering [data] records of [typeId].
+Future<ResolveResponse> _lookup(
+  RecordType type,
+  int typeId,
+  List<String> data,
+) =>
+    DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse(
+          answerBody(
+            type: typeId,
+            answer: [
+              for (final item in data)
+                answerRecord('example.com.', typeId, item),
+            ],
+          ),
+        ),
+      ),
+    ).lookup('example.com', type: type);
+
+/// Looks up [type] and checks that it is queried by its [id], and that its
+/// records keep the [data] as presented under the presentation [name].
+Future<void> _expectType(
+  RecordType type,
+  String name,
+  int id,
+  String data,
+) async {
+  final types = <String>[];
+  final dnsolve = DNSolve(
+    client: MockClient((request) async {
+      types.add(request.url.queryParameters['type']!);
+      return jsonResponse(
+        answerBody(type: id, answer: [answerRecord('example.com.', id, data)]),
+      );
+    }),
+  );
+
+  final record =
+      (await dnsolve.lookup('example.com', type: type)).answer!.records!.single;
+
+  expect(types, ['$id']);
+  expect(record.rType, type);
+  expect(record.typeId, id);
+  expect(record.typeName, name);
+  expect(record.data, data);
+  expect(record.toBind, 'example.com.\t300\tIN\t$name\t"$data"');
+}
+
+void main() {
+  group('OPENPGPKEY and SMIMEA records', () {
+    test('are looked up and presented', () async {
+      await _expectType(
+        RecordType.openpgpkey,
+        'OPENPGPKEY',
+        61,
+        'mQENBFzV0c8BCAC3mKtKzt6FqpW4uWlX8yL0o1P4sY0=',
+      );
+      await _expectType(
+        RecordType.smimea,
+        'SMIMEA',
+        53,
+        '3 0 0 308201a2300d06092a864886f70d01010105',
+      );
+    });
+  });
+
+  group('EUI48 and EUI64 records', () {
+    test('are looked up and presented', () async {
+      await _expectType(RecordType.eui48, 'EUI48', 108, '00-00-5e-00-53-2a');
+      await _expectType(
+        RecordType.eui64,
+        'EUI64',
+        109,
+        '00-00-5e-ef-10-00-00-2a',
+      );
+    });
+  });
+
+  group('APL records', () {
+    test('are looked up and presented', () async {
+      await _expectType(
+        RecordType.apl,
+        'APL',
+        42,
+        '1:192.0.2.0/24 !1:192.0.2.128/25 2:2001:db8::/32',
+      );
+    });
+  });
+
+  group('ZONEMD and CSYNC records', () {
+    test('are looked up and presented', () async {
+      await _expectType(
+        RecordType.zonemd,
+        'ZONEMD',
+        63,
+        '2018031900 1 1 c68090d90a7aed716bc459f9340e3d7c1370d4d24b7e2fc3',
+      );
+      await _expectType(RecordType.csync, 'CSYNC', 62, '66 3 A NS AAAA');
+    });
+  });
+
+  group('RP and AFSDB records', () {
+    test('are looked up and presented', () async {
+      await _expectType(
+        RecordType.rp,
+        'RP',
+        17,
+        'admin.example.com. contact.example.com.',
+      );
+      await _expectType(RecordType.afsdb, 'AFSDB', 18, '1 afs.example.com.');
+    });
+  });
+
+  group('SPF records', () {
+    test('are looked up and presented like TXT records', () async {
+      await _expectType(RecordType.spf, 'SPF', 99, '"v=spf1 -all"');
+    });
+  });
+
+  group('DHCID records', () {
+    test('are looked up and presented', () async {
+      await _expectType(
+        RecordType.dhcid,
+        'DHCID',
+        49,
+        'AAIBY2/AuCccgoJbsaxcQc9TUapptP69lOjxfNuVAA2kjEA=',
+      );
+    });
+  });
+
+  group('URI records', () {
+    test('are parsed with quoted and unquoted targets', () async {
+      final response = await _lookup(RecordType.uri, 256, [
+        '10 1 "https://example.com/a"',
+        '20 5 ftp://example.com/b',
+      ]);
+
+      final uris = response.answer!.uris;
+      expect(uris.map((uri) => uri.priority), [10, 20]);
+      expect(uris.map((uri) => uri.weight), [1, 5]);
+      expect(
+        uris.map((uri) => uri.target),
+        ['https://example.com/a', 'ftp://example.com/b'],
+      );
+      expect(uris.first.fqdn, 'example.com.');
+      expect(uris.first.toString(), '10 1 "https://example.com/a"');
+    });
+
+    test('throw when malformed', () async {
+      final response = await _lookup(RecordType.uri, 256, ['10 "https://a"']);
+
+      expect(
+        () => response.answer!.uris,
+        throwsA(isA<URIRecordFormatException>()),
+      );
+    });
+  });
+
+  group('ILNP records', () {
+    const fixture = '''
+{
+  "Status": 0,
+  "TC": false,
+  "RD": true,
+  "RA": true,
+  "AD": false,
+  "CD": false,
+  "Question": [{"name": "ilnp.example.", "type": 104}],
+  "Answer": [
+    {"name": "ilnp.example.", "type": 104, "TTL": 300,
+     "data": "10 0014:4fff:ff20:ee64"},
+    {"name": "ilnp.example.", "type": 105, "TTL": 300,
+     "data": "10 10.1.2.0"},
+    {"name": "ilnp.example.", "type": 106, "TTL": 300,
+     "data": "10 2001:0db8:1140:1000"},
+    {"name": "ilnp.example.", "type": 107, "TTL": 300,
+     "data": "10 l64-subnet1.example."}
+  ]
+}
+''';
+
+    test('are parsed from a provider answer', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => http.Response(fixture, 200)),
+      );
+
+      final response =
+          await dnsolve.lookup('ilnp.example', type: RecordType.nid);
+      final records = response.answer!.records!;
+
+      expect(
+        records.map((record) => record.rType),
+        [RecordType.nid, RecordType.l32, RecordType.l64, RecordType.lp],
+      );
+      expect(records.map((record) => record.typeId), [104, 105, 106, 107]);
+      expect(
+        records.map((record) => record.typeName),
+        ['NID', 'L32', 'L64', 'LP'],
+      );
+      expect(
+        records.map((record) => record.toBind),
+        [
+          'ilnp.example.\t300\tIN\tNID\t"10 0014:4fff:ff20:ee64"',
+          'ilnp.example.\t300\tIN\tL32\t"10 10.1.2.0"',
+          'ilnp.example.\t300\tIN\tL64\t"10 2001:0db8:1140:1000"',
+          'ilnp.example.\t300\tIN\tLP\t"10 l64-subnet1.example."',
+        ],
+      );
+    });
+  });
+}
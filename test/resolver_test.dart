@@ -0,0 +1,157 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('ping', () {
+    test('queries the root NS records once', () async {
+      final requests = <Uri>[];
+      final dnsolve = DNSolve(
+        attempts: 3,
+        client: MockClient((request) async {
+          requests.add(request.url);
+          return http.Response('unavailable', 503);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.ping(provider: DNSProvider.cloudflare),
+        throwsA(isA<ResponseException>()),
+      );
+      expect(requests, hasLength(1));
+      expect(requests.single.host, 'cloudflare-dns.com');
+      expect(requests.single.queryParameters, {'name': '.', 'type': '2'});
+    });
+
+    test('measures the round trip of an answer', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          await Future<void>.delayed(const Duration(milliseconds: 20));
+          return echoResponse(request);
+        }),
+      );
+
+      expect(
+        await dnsolve.ping(),
+        greaterThanOrEqualTo(const Duration(milliseconds: 20)),
+      );
+    });
+
+    test('reports failure statuses apart from network failures', () async {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => echoResponse(request, status: 2)),
+      );
+
+      await expectLater(
+        dnsolve.ping(),
+        throwsA(
+          isA<ResponseStatusException>()
+              .having((error) => error.kind, 'kind', DNSolveErrorKind.server),
+        ),
+      );
+    });
+  });
+
+  group('stats', () {
+    test('count answers without a status', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(answerBody(status: null)),
+        ),
+      );
+
+      await dnsolve.lookup('example.com');
+
+      expect(dnsolve.stats.queries, 1);
+      expect(dnsolve.stats.answers, 1);
+      expect(dnsolve.stats.statuses, isEmpty);
+    });
+
+    test('count failures and timeouts until reset', () async {
+      final dnsolve = DNSolve(
+        queryTimeout: const Duration(milliseconds: 20),
+        client: MockClient((request) async {
+          switch (request.url.queryParameters['name']) {
+            case 'fail.example':
+              return http.Response('unavailable', 503);
+            case 'slow.example':
+              await Future<void>.delayed(const Duration(seconds: 1));
+            case 'nx.example':
+              return echoResponse(request, status: 3);
+          }
+
+          return echoResponse(request);
+        }),
+      );
+
+      await dnsolve.lookup('example.com');
+      await dnsolve.lookup('nx.example');
+      await expectLater(
+        dnsolve.lookup('fail.example'),
+        throwsA(isA<ResponseException>()),
+      );
+      await expectLater(
+        dnsolve.lookup('slow.example'),
+        throwsA(isA<QueryTimeoutException>()),
+      );
+
+      final stats = dnsolve.stats;
+      expect(stats.queries, 4);
+      expect(stats.answers, 2);
+      expect(stats.failures, 2);
+      expect(stats.timeouts, 1);
+      expect(stats.statuses, {0: 1, 3: 1});
+
+      dnsolve.resetStats();
+
+      final reset = dnsolve.stats;
+      expect(reset.queries, 0);
+      expect(reset.answers, 0);
+      expect(reset.failures, 0);
+      expect(reset.timeouts, 0);
+      expect(reset.statuses, isEmpty);
+      expect(reset.averageLatency, Duration.zero);
+    });
+  });
+
+  group('root servers', () {
+    test('are listed in the root hints', () {
+      expect(DNSolve.rootHints, hasLength(13));
+      expect(DNSolve.rootHints.first, (
+        name: 'a.root-servers.net.',
+        ipv4: '198.41.0.4',
+        ipv6: '2001:503:ba3e::2:30',
+      ));
+      expect(
+        DNSolve.rootHints.map((hint) => hint.name),
+        everyElement(endsWith('.root-servers.net.')),
+      );
+    });
+
+    test('are looked up at the root domain', () async {
+      final requests = <Uri>[];
+      final dnsolve = DNSolve(
+        searchDomains: ['corp.example'],
+        client: MockClient((request) async {
+          requests.add(request.url);
+          return jsonResponse(
+            answerBody(
+              name: '.',
+              type: 2,
+              answer: [answerRecord('.', 2, 'a.root-servers.net.')],
+            ),
+          );
+        }),
+      );
+
+      final response = await dnsolve.lookup('.', type: RecordType.ns);
+
+      expect(requests.map((url) => url.queryParameters['name']), ['.']);
+      expect(response.answer!.records!.single.name, '.');
+      expect(response.answer!.records!.single.data, 'a.root-servers.net.');
+    });
+  });
+}
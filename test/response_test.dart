@@ -0,0 +1,112 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+/// Parses a response carrying the given provider [comment].
+ResolveResponse _commented(Object comment) =>
+    ResolveResponse.fromJson({...answerBody(status: 2), 'Comment': comment});
+
+/// An exception defined outside of the package.
+class _CustomException extends DNSolveException {
+  const _CustomException();
+}
+
+void main() {
+  group('error kinds', () {
+    test('are derived from the response status', () {
+      final kinds = {
+        for (final status in [0, 1, 2, 3, 4, 5])
+          status: ResolveResponse.fromJson(answerBody(status: status))
+              .errorKind,
+      };
+
+      expect(kinds, {
+        0: null,
+        1: DNSolveErrorKind.config,
+        2: DNSolveErrorKind.server,
+        3: DNSolveErrorKind.nxdomain,
+        4: DNSolveErrorKind.server,
+        5: DNSolveErrorKind.refused,
+      });
+    });
+
+    test('match between statuses and status exceptions', () {
+      for (final status in [1, 2, 3, 4, 5]) {
+        expect(
+          ResponseStatusException(status, 'failed').kind,
+          ResolveResponse.fromJson(answerBody(status: status)).errorKind,
+          reason: '$status',
+        );
+      }
+    });
+
+    test('default to other for exceptions defined elsewhere', () {
+      expect(const _CustomException().kind, DNSolveErrorKind.other);
+    });
+  });
+
+  group('extended DNS errors', () {
+    test('are parsed with their extra text', () {
+      final errors = _commented(
+        'EDE(9): DNSKEY Missing: no SEP matching the DS found for '
+        'dnssec-failed.org.',
+      ).extendedErrors;
+
+      expect(errors, hasLength(1));
+      expect(errors.single.code, 9);
+      expect(errors.single.purpose, 'DNSKEY Missing');
+      expect(
+        errors.single.extraText,
+        'no SEP matching the DS found for dnssec-failed.org.',
+      );
+    });
+
+    test('keep parenthesized extra text', () {
+      final error = _commented(
+        'EDE(6): DNSSEC Bogus (no valid signature found)',
+      ).extendedErrors.single;
+
+      expect(error.purpose, 'DNSSEC Bogus');
+      expect(error.extraText, 'no valid signature found');
+    });
+
+    test('keep the text of the provider for registered codes', () {
+      final error = _commented('EDE(15): blocked by policy')
+          .extendedErrors
+          .single;
+
+      expect(error.purpose, 'Blocked');
+      expect(error.extraText, 'blocked by policy');
+    });
+
+    test('are kept apart when a comment holds several', () {
+      final errors = _commented([
+        'EDE(3): Stale Answer; EDE(22): No Reachable Authority: at '
+            'delegation example.',
+        'EDE(23): Network Error',
+      ]).extendedErrors;
+
+      expect(errors.map((error) => error.code), [3, 22, 23]);
+      expect(errors.map((error) => error.extraText), [
+        null,
+        'at delegation example.',
+        null,
+      ]);
+    });
+
+    test('take the purpose of unregistered codes from the comment', () {
+      final error = _commented('EDE(49152): Vendor Policy: blocked by vendor')
+          .extendedErrors
+          .single;
+
+      expect(error.code, 49152);
+      expect(error.purpose, 'Vendor Policy');
+      expect(error.extraText, 'blocked by vendor');
+    });
+
+    test('are empty for comments without any', () {
+      expect(_commented('Response from 192.0.2.53.').extendedErrors, isEmpty);
+    });
+  });
+}
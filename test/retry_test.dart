@@ -0,0 +1,251 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('retries', () {
+    test('are not made with a single attempt', () async {
+      for (final failure in [
+        () => http.Response('unavailable', 503),
+        () => throw http.ClientException('Connection refused'),
+      ]) {
+        var requests = 0;
+        final dnsolve = DNSolve(
+          client: MockClient((request) async {
+            requests++;
+            return failure();
+          }),
+        );
+
+        await expectLater(
+          dnsolve.lookup('example.com'),
+          throwsA(
+            allOf(
+              isA<DNSolveException>(),
+              isNot(isA<AttemptsExhaustedException>()),
+            ),
+          ),
+        );
+        expect(requests, 1);
+      }
+    });
+
+    test('rotate providers on transport failures', () async {
+      final hosts = <String>[];
+      final dnsolve = DNSolve(
+        attempts: 3,
+        rotateProviders: true,
+        client: MockClient((request) async {
+          hosts.add(request.url.host);
+          return request.url.host == 'dns.google.com'
+              ? http.Response('unavailable', 503)
+              : echoResponse(request);
+        }),
+      );
+
+      final response = await dnsolve.lookup('example.com');
+
+      expect(hosts, ['dns.google.com', 'cloudflare-dns.com']);
+      expect(response.query!.provider, DNSProvider.cloudflare);
+    });
+
+    test('report every failure once attempts are exhausted', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        attempts: 2,
+        client: MockClient((request) async {
+          requests++;
+          return http.Response('unavailable', 500);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.lookup('example.com'),
+        throwsA(
+          isA<AttemptsExhaustedException>()
+              .having((error) => error.failures, 'failures', hasLength(2)),
+        ),
+      );
+      expect(requests, 2);
+    });
+
+    test('are not made for HTTP client errors', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        attempts: 3,
+        client: MockClient((request) async {
+          requests++;
+          return http.Response('bad request', 400);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.lookup('example.com'),
+        throwsA(
+          isA<ResponseException>()
+              .having((error) => error.kind, 'kind', DNSolveErrorKind.config),
+        ),
+      );
+      expect(requests, 1);
+    });
+
+    test('report earlier failures when a later one is not retried', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        attempts: 3,
+        client: MockClient((request) async {
+          requests++;
+          return requests == 1
+              ? http.Response('unavailable', 503)
+              : http.Response('bad request', 400);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.lookup('example.com'),
+        throwsA(
+          isA<AttemptsExhaustedException>()
+              .having(
+                (error) => error.failures.map((failure) => failure.$2.kind),
+                'kinds',
+                [DNSolveErrorKind.network, DNSolveErrorKind.config],
+              )
+              .having((error) => error.kind, 'kind', DNSolveErrorKind.config),
+        ),
+      );
+      expect(requests, 2);
+    });
+
+    test('report earlier failures when the deadline passes', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        attempts: 5,
+        client: MockClient((request) async {
+          requests++;
+          if (requests > 1) {
+            await Future<void>.delayed(const Duration(seconds: 1));
+          }
+
+          return http.Response('unavailable', 503);
+        }),
+      );
+
+      await expectLater(
+        dnsolve.lookup(
+          'example.com',
+          deadline: DateTime.now().add(const Duration(milliseconds: 200)),
+        ),
+        throwsA(
+          isA<AttemptsExhaustedException>().having(
+            (error) => error.failures.map((failure) => failure.$2.kind),
+            'kinds',
+            [DNSolveErrorKind.network, DNSolveErrorKind.timeout],
+          ),
+        ),
+      );
+      expect(requests, 2);
+    });
+
+    test('reject attempts out of range', () {
+      expect(() => DNSolve(attempts: 0), throwsRangeError);
+      expect(() => DNSolve(attempts: 6), throwsRangeError);
+    });
+  });
+
+  group('query timeouts', () {
+    // Returns an instance whose provider answers after a second.
+    DNSolve slow({int attempts = 1}) => DNSolve(
+          attempts: attempts,
+          queryTimeout: const Duration(milliseconds: 20),
+          client: MockClient((request) async {
+            await Future<void>.delayed(const Duration(seconds: 1));
+            return echoResponse(request);
+          }),
+        );
+
+    test('fail slow queries', () async {
+      await expectLater(
+        slow().lookup('example.com'),
+        throwsA(
+          isA<QueryTimeoutException>().having(
+            (error) => error.timeout,
+            'timeout',
+            const Duration(milliseconds: 20),
+          ),
+        ),
+      );
+    });
+
+    test('are retried', () async {
+      await expectLater(
+        slow(attempts: 2).lookup('example.com'),
+        throwsA(
+          isA<AttemptsExhaustedException>().having(
+            (error) => error.failures.map((failure) => failure.$2.kind),
+            'kinds',
+            [DNSolveErrorKind.timeout, DNSolveErrorKind.timeout],
+          ),
+        ),
+      );
+    });
+  });
+
+  group('deadlines', () {
+    test('that passed fail without sending or counting a query', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests++;
+          return echoResponse(request);
+        }),
+      );
+      final deadline = DateTime.now().subtract(const Duration(seconds: 1));
+
+      for (final lookup in [
+        () => dnsolve.lookup('example.com', deadline: deadline),
+        () => dnsolve.lookupMulti('example.com', deadline: deadline),
+      ]) {
+        await expectLater(
+          lookup(),
+          throwsA(
+            isA<QueryTimeoutException>()
+                .having((error) => error.deadline, 'deadline', deadline)
+                .having((error) => error.timeout, 'timeout', Duration.zero),
+          ),
+        );
+      }
+      expect(requests, 0);
+      expect(dnsolve.stats.queries, 0);
+      expect(dnsolve.stats.failures, 0);
+      expect(dnsolve.stats.timeouts, 0);
+    });
+
+    test('cut queries short and are reported', () async {
+      final dnsolve = DNSolve(
+        queryTimeout: const Duration(seconds: 5),
+        client: MockClient((request) async {
+          await Future<void>.delayed(const Duration(seconds: 1));
+          return echoResponse(request);
+        }),
+      );
+      final deadline = DateTime.now().add(const Duration(milliseconds: 20));
+
+      await expectLater(
+        dnsolve.lookup('example.com', deadline: deadline),
+        throwsA(
+          isA<QueryTimeoutException>()
+              .having((error) => error.deadline, 'deadline', deadline)
+              .having(
+                (error) => error.timeout,
+                'timeout',
+                lessThanOrEqualTo(const Duration(milliseconds: 20)),
+              ),
+        ),
+      );
+      expect(dnsolve.stats.timeouts, 1);
+    });
+  });
+}
@@ -0,0 +1,151 @@
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/http.dart' as http;
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+void main() {
+  group('reverse lookups', () {
+    test('expand compressed IPv6 addresses', () async {
+      final names = <String>[];
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          names.add(request.url.queryParameters['name']!);
+          return echoResponse(request);
+        }),
+      );
+
+      await dnsolve.reverseLookup('2001:db8::1');
+      await dnsolve.reverseLookup('192.0.2.1');
+
+      expect(names, [
+        '1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2'
+            '.ip6.arpa',
+        '1.2.0.192.in-addr.arpa',
+      ]);
+    });
+
+    test('sort PTR names case-insensitively', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => jsonResponse(
+            answerBody(
+              type: 12,
+              answer: [
+                answerRecord('1.2.0.192.in-addr.arpa.', 12, 'b.example.'),
+                answerRecord('1.2.0.192.in-addr.arpa.', 12, 'C.example.'),
+                answerRecord('1.2.0.192.in-addr.arpa.', 12, 'a.example.'),
+              ],
+            ),
+          ),
+        ),
+      );
+
+      final unsorted = await dnsolve.reverseLookup('192.0.2.1');
+      final sorted = await dnsolve.reverseLookup('192.0.2.1', sort: true);
+
+      expect(
+        unsorted.map((record) => record.data),
+        ['b.example.', 'C.example.', 'a.example.'],
+      );
+      expect(
+        sorted.map((record) => record.data),
+        ['a.example.', 'b.example.', 'C.example.'],
+      );
+    });
+
+    test('reject malformed addresses without querying them', () async {
+      var requests = 0;
+      final dnsolve = DNSolve(
+        client: MockClient((request) async {
+          requests++;
+          return echoResponse(request);
+        }),
+      );
+
+      for (final ip in ['999.1.1', '192.0.2.256', 'example', '2001:db8::zz']) {
+        await expectLater(
+          dnsolve.reverseLookup(ip),
+          throwsA(isA<AddressFormatException>()),
+          reason: ip,
+        );
+      }
+      expect(requests, 0);
+    });
+
+    test('keep the error of each failed address in a batch', () async {
+      final dnsolve = DNSolve(
+        client: MockClient(
+          (request) async => request.url.queryParameters['name']!
+                  .startsWith('2.')
+              ? http.Response('unavailable', 500)
+              : jsonResponse(
+                  answerBody(
+                    type: 12,
+                    answer: [
+                      answerRecord('1.2.0.192.in-addr.arpa.', 12, 'a.example.'),
+                    ],
+                  ),
+                ),
+        ),
+      );
+
+      final results = await dnsolve.reverseLookupBatch(
+        ['192.0.2.1', '192.0.2.2', '2001:db8::zz', '999.1.1'],
+      );
+
+      expect(results[0].records, hasLength(1));
+      expect(results[0].error, isNull);
+      expect(results[1].records, isEmpty);
+      expect(results[1].error, isA<ResponseException>());
+      expect(results[2].error, isA<AddressFormatException>());
+      expect(results[3].records, isEmpty);
+      expect(results[3].error, isA<AddressFormatException>());
+    });
+
+    group('of CIDR ranges', () {
+      final dnsolve = DNSolve(
+        client: MockClient((request) async => echoResponse(request, status: 3)),
+      );
+
+      test('skip the IPv4 network and broadcast addresses', () async {
+        final results = await dnsolve.reverseLookupCidr('192.0.2.0/30');
+
+        expect(results.keys, ['192.0.2.1', '192.0.2.2']);
+      });
+
+      test('enumerate IPv6 ranges', () async {
+        final results = await dnsolve.reverseLookupCidr('2001:db8::/127');
+
+        expect(results.keys, [
+          '2001:0db8:0000:0000:0000:0000:0000:0000',
+          '2001:0db8:0000:0000:0000:0000:0000:0001',
+        ]);
+      });
+
+      test('accept the largest allowed ranges', () async {
+        final ipv4 = await dnsolve.reverseLookupCidr('192.0.0.0/20');
+        final ipv6 = await dnsolve.reverseLookupCidr('2001:db8::/116');
+
+        expect(ipv4, hasLength(4094));
+        expect(ipv6, hasLength(4096));
+      });
+
+      for (final cidr in [
+        '192.0.0.0/19',
+        '2001:db8::/115',
+        '192.0.2.0/33',
+        '192.0.2.0',
+        '192.0.2/24',
+      ]) {
+        test('reject $cidr', () async {
+          await expectLater(
+            dnsolve.reverseLookupCidr(cidr),
+            throwsA(isA<AddressFormatException>()),
+          );
+        });
+      }
+    });
+  });
+}
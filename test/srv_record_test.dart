@@ -0,0 +1,91 @@
+import 'dart:math';
+
+import 'package:dnsolve/dnsolve.dart';
+import 'package:http/testing.dart';
+import 'package:test/test.dart';
+
+import 'helpers.dart';
+
+/// Returns an instance answering every query with the given records.
+DNSolve _answering(int type, List<Map<String, Object?>> answer) => DNSolve(
+      client: MockClient(
+        (request) async => jsonResponse(answerBody(type: type, answer: answer)),
+      ),
+    );
+
+void main() {
+  group('sorted answers', () {
+    test('order MX records by preference', () async {
+      final response = await _answering(15, [
+        answerRecord('example.com.', 15, '20 b.example.'),
+        answerRecord('example.com.', 15, '10 a.example.'),
+        answerRecord('example.com.', 15, '20 c.example.'),
+      ]).lookup('example.com', type: RecordType.mx, sort: true);
+
+      expect(
+        response.answer!.records!.map((record) => record.data),
+        ['10 a.example.', '20 b.example.', '20 c.example.'],
+      );
+    });
+
+    test('order SRV records by priority, then descending weight', () async {
+      final response = await _answering(33, [
+        answerRecord('_sip._tcp.example.', 33, '20 0 5060 d.example.'),
+        answerRecord('_sip._tcp.example.', 33, '10 10 5060 c.example.'),
+        answerRecord('_sip._tcp.example.', 33, '10 60 5060 a.example.'),
+        answerRecord('_sip._tcp.example.', 33, '10 10 5060 e.example.'),
+        answerRecord('_sip._tcp.example.', 33, '10 30 5060 b.example.'),
+      ]).lookup('example.com', type: RecordType.srv, sort: true);
+
+      const order = [
+        'a.example.',
+        'b.example.',
+        'c.example.',
+        'e.example.',
+        'd.example.',
+      ];
+      expect(
+        response.answer!.records!.map((record) => record.data.split(' ').last),
+        order,
+      );
+      expect(response.answer!.srvs!.map((srv) => srv.target), order);
+    });
+  });
+
+  group('SRVRecord.shuffle', () {
+    const records = [
+      SRVRecord(priority: 20, weight: 0, port: 1, target: 'c.', fqdn: 's.'),
+      SRVRecord(priority: 10, weight: 60, port: 1, target: 'a.', fqdn: 's.'),
+      SRVRecord(priority: 10, weight: 40, port: 1, target: 'b.', fqdn: 's.'),
+      SRVRecord(priority: 30, weight: 10, port: 1, target: 'd.', fqdn: 's.'),
+    ];
+
+    test('orders records by ascending priority', () {
+      final shuffled = SRVRecord.shuffle(records, Random(1));
+
+      expect(shuffled.map((record) => record.priority), [10, 10, 20, 30]);
+      expect(shuffled, unorderedEquals(records));
+    });
+
+    test('is reproducible with a seeded random', () {
+      for (var seed = 0; seed < 10; seed++) {
+        expect(
+          SRVRecord.shuffle(records, Random(seed)),
+          SRVRecord.shuffle(records, Random(seed)),
+        );
+      }
+    });
+
+    test('favors records with a higher weight', () {
+      final random = Random(7);
+      var heavierFirst = 0;
+      for (var i = 0; i < 1000; i++) {
+        if (SRVRecord.shuffle(records, random).first.target == 'a.') {
+          heavierFirst++;
+        }
+      }
+
+      expect(heavierFirst, inInclusiveRange(500, 700));
+    });
+  });
+}